@@ -0,0 +1,73 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+///Binary content, represented as a string using the standard base64 encoding.
+///It is used to exchange binary attributes through the string based interfaces
+///(Object and ObjectFactory).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Base64(pub Vec<u8>);
+
+impl Base64 {
+    ///Encode the given bytes as base64
+    pub fn encode<T: AsRef<[u8]>>(bytes: T) -> String {
+        STANDARD.encode(bytes)
+    }
+}
+
+impl From<Base64> for Vec<u8> {
+    fn from(value: Base64) -> Self {
+        value.0
+    }
+}
+
+impl std::fmt::Display for Base64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Base64::encode(&self.0))
+    }
+}
+
+impl std::str::FromStr for Base64 {
+    type Err = base64::DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Base64(STANDARD.decode(s)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base64;
+    use crate::extract_optional;
+
+    #[test]
+    fn round_trip() {
+        let encoded = Base64::encode([0u8, 1, 2, 255]);
+        assert_eq!("AAEC/w==", encoded);
+        assert_eq!(Ok(Base64(vec![0, 1, 2, 255])), encoded.parse());
+    }
+
+    #[test]
+    fn optional() {
+        assert_eq!(Ok(Some(Base64(vec![104, 105]))), extract_optional("(aGk=)"));
+        assert!(extract_optional::<Base64>("(not base64)").is_err());
+    }
+}
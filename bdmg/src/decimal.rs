@@ -0,0 +1,144 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use diesel::deserialize::FromSql;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::{Sqlite, SqliteValue};
+
+///An exact decimal number, stored on database as text.
+///
+///The value is stored without its trailing zeros, so that `1.5` and `1.50`
+///have the same representation on database. The text representation is
+///used because SQLite would convert a numeric column into a floating point.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+pub struct Decimal(pub rust_decimal::Decimal);
+
+impl Decimal {
+    ///Check if the value can be represented with at most `precision` significant
+    ///digits, of which `scale` are after the decimal point
+    pub fn fits(&self, precision: u32, scale: u32) -> bool {
+        let normalized = self.0.normalize();
+        if normalized.scale() > scale || scale > precision {
+            return false;
+        }
+        match 10i128.checked_pow(precision - scale) {
+            Some(limit) => normalized.trunc().abs().mantissa() < limit,
+            None => true,
+        }
+    }
+}
+
+impl From<rust_decimal::Decimal> for Decimal {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Decimal(value)
+    }
+}
+
+impl From<Decimal> for rust_decimal::Decimal {
+    fn from(value: Decimal) -> Self {
+        value.0
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for Decimal {
+    type Err = rust_decimal::Error;
+
+    ///Parse a decimal number. Contrary to the parsing of rust_decimal, a value
+    ///that can not be represented without rounding is refused
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Decimal(rust_decimal::Decimal::from_str_exact(s)?))
+    }
+}
+
+impl serde::Serialize for Decimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Decimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse::<Decimal>().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql<Text, Sqlite> for Decimal {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.0.normalize().to_string());
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Sqlite> for Decimal {
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(value.parse::<Decimal>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decimal;
+
+    #[test]
+    fn parse() {
+        let value: Decimal = "12.50".parse().unwrap();
+        assert_eq!("12.50", value.to_string());
+        assert_eq!(Ok(value), "12.5".parse());
+        assert!("0.1234567890123456789012345678901".parse::<Decimal>().is_err());
+        assert!("1.5e3".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn fits() {
+        let value: Decimal = "-123.40".parse().unwrap();
+        assert!(value.fits(5, 2));
+        assert!(value.fits(4, 1));
+        assert!(!value.fits(3, 0));
+        assert!(!value.fits(4, 2));
+        assert!(!value.fits(28, 29));
+    }
+}
@@ -0,0 +1,269 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use diesel::deserialize::FromSql;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::BigInt;
+use diesel::sqlite::{Sqlite, SqliteValue};
+
+///A duration, stored on database as a number of microseconds.
+///
+///The string representation of a duration is the ISO 8601 format
+///`[-]P[nD][T[nH][nM][n[.f]S]]`, e.g. `PT1H30M` or `P2DT0.5S`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+)]
+#[diesel(sql_type = BigInt)]
+pub struct Duration {
+    microseconds: i64,
+}
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+impl Duration {
+    ///Create a duration from a number of microseconds
+    pub fn from_microseconds(microseconds: i64) -> Duration {
+        Duration { microseconds }
+    }
+
+    ///Retrieve the number of microseconds of this duration
+    pub fn as_microseconds(&self) -> i64 {
+        self.microseconds
+    }
+
+    ///Retrieve the chrono representation of this duration
+    pub fn to_chrono(&self) -> chrono::Duration {
+        chrono::Duration::microseconds(self.microseconds)
+    }
+}
+
+impl From<chrono::Duration> for Duration {
+    ///Convert a chrono duration. The sub-microsecond part is truncated and
+    ///durations that do not fit in an i64 number of microseconds are saturated.
+    fn from(duration: chrono::Duration) -> Self {
+        Duration {
+            microseconds: duration.num_microseconds().unwrap_or(if duration < chrono::Duration::zero() {
+                i64::MIN
+            } else {
+                i64::MAX
+            }),
+        }
+    }
+}
+
+impl From<Duration> for chrono::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.to_chrono()
+    }
+}
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.microseconds < 0 { "-" } else { "" };
+        let mut remaining = self.microseconds.unsigned_abs();
+        let days = remaining / MICROS_PER_DAY as u64;
+        remaining %= MICROS_PER_DAY as u64;
+        let hours = remaining / MICROS_PER_HOUR as u64;
+        remaining %= MICROS_PER_HOUR as u64;
+        let minutes = remaining / MICROS_PER_MINUTE as u64;
+        remaining %= MICROS_PER_MINUTE as u64;
+        let seconds = remaining / MICROS_PER_SECOND as u64;
+        let fraction = remaining % MICROS_PER_SECOND as u64;
+
+        write!(f, "{sign}P")?;
+        if days > 0 {
+            write!(f, "{days}D")?;
+        }
+        if hours == 0 && minutes == 0 && seconds == 0 && fraction == 0 {
+            if days == 0 {
+                write!(f, "T0S")?;
+            }
+            return Ok(());
+        }
+        write!(f, "T")?;
+        if hours > 0 {
+            write!(f, "{hours}H")?;
+        }
+        if minutes > 0 {
+            write!(f, "{minutes}M")?;
+        }
+        if fraction > 0 {
+            let fraction = format!("{fraction:06}");
+            write!(f, "{seconds}.{}S", fraction.trim_end_matches('0'))
+        } else if seconds > 0 {
+            write!(f, "{seconds}S")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+///Error returned when a string is not a valid ISO 8601 duration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDurationError(String);
+
+impl std::fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid duration '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+impl std::str::FromStr for Duration {
+    type Err = ParseDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDurationError(s.to_string());
+        let (negative, content) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let content = content.strip_prefix('P').ok_or_else(error)?;
+        if content.is_empty() {
+            return Err(error());
+        }
+
+        let mut microseconds: i64 = 0;
+        let mut in_time = false;
+        let mut number = String::new();
+        for c in content.chars() {
+            match c {
+                '0'..='9' | '.' => number.push(c),
+                'T' if !in_time && number.is_empty() => in_time = true,
+                'D' | 'H' | 'M' | 'S' => {
+                    let unit = match (c, in_time) {
+                        ('D', false) => MICROS_PER_DAY,
+                        ('H', true) => MICROS_PER_HOUR,
+                        ('M', true) => MICROS_PER_MINUTE,
+                        ('S', true) => MICROS_PER_SECOND,
+                        _ => return Err(error()),
+                    };
+                    let value = match number.split_once('.') {
+                        None => number
+                            .parse::<i64>()
+                            .ok()
+                            .and_then(|v| v.checked_mul(unit)),
+                        //only the seconds may have a fractional part, up to the microsecond
+                        Some((integer, fraction)) if c == 'S' && fraction.len() <= 6 => {
+                            let fraction = format!("{fraction:0<6}").parse::<i64>().ok();
+                            integer
+                                .parse::<i64>()
+                                .ok()
+                                .and_then(|v| v.checked_mul(unit))
+                                .zip(fraction)
+                                .and_then(|(v, f)| v.checked_add(f))
+                        }
+                        Some(_) => None,
+                    };
+                    microseconds = value
+                        .and_then(|v| microseconds.checked_add(v))
+                        .ok_or_else(error)?;
+                    number.clear();
+                }
+                _ => return Err(error()),
+            }
+        }
+        if !number.is_empty() || content.ends_with('T') {
+            return Err(error());
+        }
+        Ok(Duration {
+            microseconds: if negative { -microseconds } else { microseconds },
+        })
+    }
+}
+
+impl serde::Serialize for Duration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Duration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse::<Duration>().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql<BigInt, Sqlite> for Duration {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.microseconds);
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<BigInt, Sqlite> for Duration {
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+        Ok(Duration {
+            microseconds: <i64 as FromSql<BigInt, Sqlite>>::from_sql(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Duration;
+
+    #[test]
+    fn display() {
+        assert_eq!("PT0S", Duration::from_microseconds(0).to_string());
+        assert_eq!("PT1H30M", Duration::from_microseconds(5_400_000_000).to_string());
+        assert_eq!("P2DT0.5S", Duration::from_microseconds(172_800_500_000).to_string());
+        assert_eq!("-P1D", Duration::from_microseconds(-86_400_000_000).to_string());
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Duration::from_microseconds(0)), "PT0S".parse());
+        assert_eq!(Ok(Duration::from_microseconds(5_400_000_000)), "PT1H30M".parse());
+        assert_eq!(Ok(Duration::from_microseconds(172_800_500_000)), "P2DT0.5S".parse());
+        assert_eq!(Ok(Duration::from_microseconds(-1)), "-PT0.000001S".parse());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!("".parse::<Duration>().is_err());
+        assert!("P".parse::<Duration>().is_err());
+        assert!("PT".parse::<Duration>().is_err());
+        assert!("P1H".parse::<Duration>().is_err());
+        assert!("PT1.5M".parse::<Duration>().is_err());
+        assert!("PT0.0000001S".parse::<Duration>().is_err());
+        assert!("12".parse::<Duration>().is_err());
+    }
+}
@@ -0,0 +1,210 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use diesel::deserialize::FromSql;
+use diesel::dsl::sql;
+use diesel::expression::BoxableExpression;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::{BigInt, Bool, Double, Text};
+use diesel::sqlite::{Sqlite, SqliteValue};
+
+///A JSON document, stored on database as text.
+///
+///By default, the document is an untyped serde_json::Value. Any type implementing
+///Serialize and Deserialize can be used instead to have a typed document.
+#[derive(Debug, Clone, PartialEq, Default, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = Text)]
+pub struct Json<T = serde_json::Value>(pub T);
+
+impl<T> Json<T> {
+    ///Retrieve the document
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl Json<serde_json::Value> {
+    ///Convert the untyped document into the given type
+    pub fn to_typed<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.0.clone())
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Json(value)
+    }
+}
+
+impl<T> std::ops::Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: serde::Serialize> std::fmt::Display for Json<T> {
+    ///Write the compact JSON representation of the document
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match serde_json::to_string(&self.0) {
+            Ok(s) => write!(f, "{}", s),
+            Err(_e) => Err(std::fmt::Error),
+        }
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> std::str::FromStr for Json<T> {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Json(serde_json::from_str(s)?))
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Json<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Json<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Json(T::deserialize(deserializer)?))
+    }
+}
+
+impl<T> ToSql<Text, Sqlite> for Json<T>
+where
+    T: serde::Serialize + std::fmt::Debug,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(serde_json::to_string(&self.0)?);
+        Ok(IsNull::No)
+    }
+}
+
+impl<T> FromSql<Text, Sqlite> for Json<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(Json(serde_json::from_str(&value)?))
+    }
+}
+
+///Build the condition checking that the JSON document stored in the column contains
+///the given value at the given path (e.g. `$.settings.theme`), using the SQLite
+///json functions. The path and the value are bound as parameters of the query.
+///
+///Objects and arrays are compared on their compact representation, so the order
+///of the keys of an object matters.
+pub fn json_path_condition<QS>(
+    column: &str,
+    path: &str,
+    value: &serde_json::Value,
+) -> Box<dyn BoxableExpression<QS, Sqlite, SqlType = Bool>> {
+    let extract = format!("json_extract({column}, ");
+    //json_extract converts the booleans into integers, the type of the value is checked first
+    let number = format!("json_type({column}, ");
+    let number_extract = format!(") IN ('integer', 'real') AND {extract}");
+    let path = path.to_string();
+    match value {
+        //json_extract does not make the difference between a missing value, null and booleans
+        serde_json::Value::Null => Box::new(
+            sql::<Bool>(&format!("json_type({column}, "))
+                .bind::<Text, _>(path)
+                .sql(") = 'null'"),
+        ),
+        serde_json::Value::Bool(b) => Box::new(
+            sql::<Bool>(&format!("json_type({column}, "))
+                .bind::<Text, _>(path)
+                .sql(if *b { ") = 'true'" } else { ") = 'false'" }),
+        ),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Box::new(
+                sql::<Bool>(&number)
+                    .bind::<Text, _>(path.clone())
+                    .sql(&number_extract)
+                    .bind::<Text, _>(path)
+                    .sql(") = ")
+                    .bind::<BigInt, _>(i),
+            ),
+            None => Box::new(
+                sql::<Bool>(&number)
+                    .bind::<Text, _>(path.clone())
+                    .sql(&number_extract)
+                    .bind::<Text, _>(path)
+                    .sql(") = ")
+                    .bind::<Double, _>(n.as_f64().unwrap_or(f64::NAN)),
+            ),
+        },
+        serde_json::Value::String(s) => Box::new(
+            sql::<Bool>(&extract)
+                .bind::<Text, _>(path)
+                .sql(") = ")
+                .bind::<Text, _>(s.clone()),
+        ),
+        other => Box::new(
+            sql::<Bool>(&extract)
+                .bind::<Text, _>(path)
+                .sql(") = json(")
+                .bind::<Text, _>(other.to_string())
+                .sql(")"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+    use crate::extract_optional;
+
+    #[test]
+    fn round_trip() {
+        let value: Json = "{\"a\": [1, true, null]}".parse().unwrap();
+        assert_eq!("{\"a\":[1,true,null]}", value.to_string());
+        assert_eq!(Some(&serde_json::json!(true)), value["a"].get(1));
+        assert!("{\"a\":".parse::<Json>().is_err());
+    }
+
+    #[test]
+    fn typed() {
+        let value: Json<Vec<i64>> = "[1, 2]".parse().unwrap();
+        assert_eq!(vec![1, 2], value.into_inner());
+        assert_eq!(
+            Ok(Some(Json(vec![3]))),
+            extract_optional::<Json<Vec<i64>>>("([3])").map_err(|e| e.to_string())
+        );
+        assert!("[\"x\"]".parse::<Json<Vec<i64>>>().is_err());
+    }
+}
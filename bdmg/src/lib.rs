@@ -20,6 +20,26 @@ extern crate diesel;
 #[macro_use]
 extern crate serde_derive;
 
+mod base64;
+mod decimal;
+mod duration;
+mod json;
+mod migration;
+mod uuid;
+
+pub use self::base64::Base64;
+pub use self::uuid::{ParseUuidError, Uuid};
+pub use chrono;
+pub use decimal::Decimal;
+pub use duration::{Duration, ParseDurationError};
+pub use json::{json_path_condition, Json};
+pub use migration::{
+    get_database_version, revert_migrations, run_migrations, Migration, MIGRATIONS_TABLE,
+};
+pub use regex;
+pub use rust_decimal;
+pub use serde_json;
+
 ///Trait to provide the name of the table that holds the different
 ///records for an object
 pub trait SqlRepresentation {
@@ -31,22 +51,90 @@ pub trait SqlRepresentation {
     fn get_object_introspection() -> Box<dyn ObjectIntrospection>;
 }
 
+///The identifier of an instance, independently of the type of primary key
+///used by its object
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(untagged)]
+pub enum ObjectId {
+    ///The identifier of an object using an autoincremented integer as primary key
+    Integer(i32),
+    ///The identifier of an object using an UUID as primary key
+    Uuid(Uuid),
+}
+
+impl From<i32> for ObjectId {
+    fn from(id: i32) -> Self {
+        ObjectId::Integer(id)
+    }
+}
+
+impl From<Uuid> for ObjectId {
+    fn from(id: Uuid) -> Self {
+        ObjectId::Uuid(id)
+    }
+}
+
+impl TryFrom<ObjectId> for i32 {
+    type Error = Error;
+
+    fn try_from(id: ObjectId) -> Result<Self, Self::Error> {
+        match id {
+            ObjectId::Integer(v) => Ok(v),
+            other => Err(Error::InvalidIdentifier(other)),
+        }
+    }
+}
+
+impl TryFrom<ObjectId> for Uuid {
+    type Error = Error;
+
+    fn try_from(id: ObjectId) -> Result<Self, Self::Error> {
+        match id {
+            ObjectId::Uuid(v) => Ok(v),
+            other => Err(Error::InvalidIdentifier(other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectId::Integer(v) => write!(f, "{}", v),
+            ObjectId::Uuid(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl std::str::FromStr for ObjectId {
+    type Err = ParseUuidError;
+
+    ///Parse an identifier: an integer gives an ObjectId::Integer, anything
+    ///else is expected to be an UUID
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<i32>() {
+            Ok(v) => Ok(ObjectId::Integer(v)),
+            Err(_e) => Ok(ObjectId::Uuid(s.parse::<Uuid>()?)),
+        }
+    }
+}
+
 ///This struct is providing an iterator interface to some objects
 ///The return type of the iterator is a result containing a pointer to the trait Object.
 ///The idea is that the iteration loads only one object at a time from the database.
 pub struct ObjectIterator<'a> {
     ///The next id to be loaded. Note that this id might be deleted between being set
-    ///and when we want to load it. Therefore, extra caution needs to be taken
-    next_id: i32,
+    ///and when we want to load it. Therefore, extra caution needs to be taken.
+    ///None if there is nothing left to load
+    next_id: Option<ObjectId>,
     ///The last id that the iterator should provide. Note that if new items are added
-    ///to the database, those won't be seen. And, as for next_id, it is possible that
+    ///to the database after it, those won't be seen. And, as for next_id, it is possible that
     ///the element with id last_id has been deleted in the mean time
-    last_id: i32,
+    last_id: Option<ObjectId>,
     retriever: fn(
-        i32,
-        i32,
+        ObjectId,
+        ObjectId,
         &mut diesel::sqlite::SqliteConnection,
-    ) -> (i32, Option<Result<Box<(dyn Object + 'static)>, String>>),
+    ) -> (Option<ObjectId>, Option<Result<Box<dyn Object + 'static>, String>>),
     ///The connection that will be used to the database
     connection: &'a mut diesel::sqlite::SqliteConnection,
 }
@@ -54,18 +142,22 @@ pub struct ObjectIterator<'a> {
 impl<'a> ObjectIterator<'a> {
     ///Create a new object iterator.
     ///This function should provide all accessible records with id's between
-    ///first_id to final_id (included). To retrieve the objects, the object
-    ///iterator will use the function retrieval_function to do so.
+    ///first_id to final_id (included), in the order of the ids. If one of them
+    ///is None, there is nothing to iterate on. To retrieve the objects, the object
+    ///iterator will use the function retrieval_function to do so. It returns
+    ///the id following next_id, if any, and the instance with the id next_id.
     pub fn new(
-        first_id: i32,
-        final_id: i32,
+        first_id: Option<ObjectId>,
+        final_id: Option<ObjectId>,
         connect: &'a mut diesel::sqlite::SqliteConnection,
         retrieval_function: fn(
-            next_id: i32,
-            last_id: i32,
+            next_id: ObjectId,
+            last_id: ObjectId,
             connection: &mut diesel::sqlite::SqliteConnection,
-        )
-            -> (i32, Option<Result<Box<(dyn Object + 'static)>, String>>),
+        ) -> (
+            Option<ObjectId>,
+            Option<Result<Box<dyn Object + 'static>, String>>,
+        ),
     ) -> ObjectIterator<'a> {
         ObjectIterator {
             next_id: first_id,
@@ -79,12 +171,13 @@ impl<'a> ObjectIterator<'a> {
 impl<'a> Iterator for ObjectIterator<'a> {
     type Item = Result<Box<(dyn Object + 'static)>, String>;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.next_id > self.last_id {
-            None
-        } else {
-            let retrieved = (self.retriever)(self.next_id, self.last_id, &mut self.connection);
-            self.next_id = retrieved.0;
-            retrieved.1
+        match (self.next_id, self.last_id) {
+            (Some(next_id), Some(last_id)) if next_id <= last_id => {
+                let retrieved = (self.retriever)(next_id, last_id, self.connection);
+                self.next_id = retrieved.0;
+                retrieved.1
+            }
+            _ => None,
         }
     }
 }
@@ -105,6 +198,29 @@ pub trait ObjectFactory {
 pub enum AttributeType {
     ///An integer type
     Integer,
+    ///A floating point type
+    Float,
+    ///An exact decimal number with at most `precision` digits, of which `scale`
+    ///are after the decimal point
+    Decimal { precision: u32, scale: u32 },
+    ///A boolean type, represented as `true` or `false`
+    Boolean,
+    ///A date, represented as `YYYY-MM-DD`
+    Date,
+    ///A time of the day, represented as `hh:mm:ss[.f]`
+    Time,
+    ///A UTC timestamp, represented following the RFC 3339
+    Timestamp,
+    ///A duration, represented following the ISO 8601 (`PnDTnHnMnS`)
+    Duration,
+    ///Binary content, represented using the base64 encoding
+    Bytes,
+    ///An UUID, represented as `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`
+    Uuid,
+    ///A JSON document, represented with its compact JSON representation
+    Json,
+    ///A value of an enumeration: the name of the enumeration and the allowed values
+    Enum { name: String, values: Vec<String> },
     ///A string type
     String,
     ///A reference to some other object (value of the related string)
@@ -133,6 +249,13 @@ pub enum Error {
     UnableToRetrieveIdentifierForTable(String),
     UnableToCreateRecord(String),
     InvalidVersion,
+    InvalidIdentifier(ObjectId),
+    ///The database was migrated to a version of the model more recent than the compiled one
+    DatabaseTooRecent { database: i64, compiled: i64 },
+    ///A migration recorded in the database is not the one of the compiled model
+    UnknownMigration { version: i64, name: String },
+    ///A migration could not be applied or reverted
+    MigrationFailed { name: String, reason: String },
 }
 
 impl std::fmt::Display for Error {
@@ -160,6 +283,22 @@ impl std::fmt::Display for Error {
                 write!(f, "Unable to create a record of type '{}'", record_type)
             }
             Error::InvalidVersion => write!(f, "The requested version of the object is not valid"),
+            Error::InvalidIdentifier(id) => {
+                write!(f, "The identifier '{}' is not of the type used by the object", id)
+            }
+            Error::DatabaseTooRecent { database, compiled } => write!(
+                f,
+                "The database is at the version {} of the model, more recent than the version {}",
+                database, compiled
+            ),
+            Error::UnknownMigration { version, name } => write!(
+                f,
+                "The migration '{}' applied to the database for the version {} is not known",
+                name, version
+            ),
+            Error::MigrationFailed { name, reason } => {
+                write!(f, "Unable to apply the migration '{}': {}", name, reason)
+            }
         }
     }
 }
@@ -260,7 +399,7 @@ pub trait ObjectIntrospection {
     fn get_object(
         &self,
         connection: &mut diesel::sqlite::SqliteConnection,
-        id: i32,
+        id: ObjectId,
         version: Option<i64>,
     ) -> Result<Box<(dyn Object + 'static)>, Error>;
     ///Retrieve the current number of instances
@@ -280,7 +419,7 @@ pub trait ObjectIntrospection {
     fn get_referencing(
         &self,
         connection: &mut diesel::sqlite::SqliteConnection,
-        instance_id: i32,
+        instance_id: ObjectId,
         ref_table: &str,
         ref_attribute: &str,
     ) -> Result<Vec<Box<dyn Object>>, Error>;
@@ -293,7 +432,7 @@ pub trait ObjectIntrospection {
     fn get_related(
         &self,
         connection: &mut diesel::sqlite::SqliteConnection,
-        instance_id: i32,
+        instance_id: ObjectId,
         related_object: &str,
         relation_object: &str,
         referencing_attribute: &str,
@@ -305,7 +444,7 @@ pub trait Object {
     ///Retrieve the type name of this object
     fn type_name(&self) -> &'static str;
     ///Retrieve the identifier of the object
-    fn get_id(&self) -> i32;
+    fn get_id(&self) -> ObjectId;
     ///Retrieve the version of the object
     fn get_version(&self) -> i64;
     ///Retrieve the string representation of an attribute.
@@ -326,6 +465,35 @@ pub trait Object {
     ) -> Result<(), Error>;
 }
 
+/// Error returned when a string is not the name of a variant of an enumeration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    enumeration: &'static str,
+    value: String,
+}
+
+impl ParseEnumError {
+    ///Create a new error for the given enumeration and the value that could not be parsed
+    pub fn new(enumeration: &'static str, value: &str) -> ParseEnumError {
+        ParseEnumError {
+            enumeration,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is not a variant of the enumeration {}",
+            self.value, self.enumeration
+        )
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
+
 /// Enumeration to represent the error that might happen when trying to convert
 /// an optional value represented as string to the rust typed representation
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -0,0 +1,354 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use diesel::connection::SimpleConnection;
+use diesel::query_dsl::methods::ExecuteDsl;
+use diesel::sql_types::{BigInt, Text};
+use diesel::sqlite::SqliteConnection;
+use diesel::RunQueryDsl;
+
+use crate::Error;
+
+///The name of the table recording the migrations applied to a database
+pub const MIGRATIONS_TABLE: &str = "bdmg_migrations";
+
+///A migration of the database: the scripts bringing the schema from the previous version
+///of the model to the next one, and back. The version of the model reached by a migration
+///is its position in the list of migrations, starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    name: &'static str,
+    up: &'static str,
+    down: &'static str,
+}
+
+impl Migration {
+    ///Create a migration from its name and its scripts
+    pub const fn new(name: &'static str, up: &'static str, down: &'static str) -> Migration {
+        Migration { name, up, down }
+    }
+
+    ///Retrieve the name of the migration
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    ///Retrieve the script applying the migration
+    pub fn get_up_script(&self) -> &'static str {
+        self.up
+    }
+
+    ///Retrieve the script reverting the migration
+    pub fn get_down_script(&self) -> &'static str {
+        self.down
+    }
+}
+
+///A migration recorded in the bookkeeping table
+#[derive(diesel::QueryableByName)]
+struct AppliedMigration {
+    #[diesel(sql_type = BigInt)]
+    version: i64,
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+///The result of the queries counting rows
+#[derive(diesel::QueryableByName)]
+struct Count {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+///Retrieve the version of the model recorded in the database, 0 if no migration was applied
+pub fn get_database_version(connection: &mut SqliteConnection) -> Result<i64, Error> {
+    let tables: Count = diesel::sql_query(
+        "SELECT COUNT(*) AS count FROM sqlite_master WHERE type = 'table' AND name = ?",
+    )
+    .bind::<Text, _>(MIGRATIONS_TABLE)
+    .get_result(connection)?;
+    if tables.count == 0 {
+        return Ok(0);
+    }
+    Ok(get_applied_migrations(connection)?
+        .last()
+        .map_or(0, |applied| applied.version))
+}
+
+///Apply the migrations that are not yet applied to the database, in order. Each migration
+///is applied in its own transaction, along with its record in the bookkeeping table, and
+///with the foreign keys disabled so that the tables can be rebuilt. The migrations are
+///refused if the database was migrated with migrations unknown to the given list.
+///The applied migrations are returned
+pub fn run_migrations<'a>(
+    connection: &mut SqliteConnection,
+    migrations: &'a [Migration],
+) -> Result<Vec<&'a Migration>, Error> {
+    connection.batch_execute(&format!(
+        "CREATE TABLE IF NOT EXISTS {MIGRATIONS_TABLE} (
+    version BIGINT PRIMARY KEY NOT NULL,
+    name VARCHAR NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);"
+    ))?;
+    let current = check_applied_migrations(connection, migrations)?;
+    let mut applied = Vec::new();
+    for (index, migration) in migrations.iter().enumerate().skip(current) {
+        let version = index as i64 + 1;
+        let record = diesel::sql_query(format!(
+            "INSERT INTO {MIGRATIONS_TABLE} (version, name) VALUES (?, ?)"
+        ))
+        .bind::<BigInt, _>(version)
+        .bind::<Text, _>(migration.name);
+        apply_script(connection, migration.name, migration.up, record)?;
+        applied.push(migration);
+    }
+    Ok(applied)
+}
+
+///Revert the migrations applied to the database after the given version of the model, in
+///the reverse order. The reverted migrations are returned
+pub fn revert_migrations<'a>(
+    connection: &mut SqliteConnection,
+    migrations: &'a [Migration],
+    version: i64,
+) -> Result<Vec<&'a Migration>, Error> {
+    let current = match get_database_version(connection)? {
+        0 => 0,
+        _ => check_applied_migrations(connection, migrations)?,
+    };
+    let mut reverted = Vec::new();
+    for (index, migration) in migrations.iter().enumerate().take(current).rev() {
+        let migration_version = index as i64 + 1;
+        if migration_version <= version {
+            break;
+        }
+        let record = diesel::sql_query(format!("DELETE FROM {MIGRATIONS_TABLE} WHERE version = ?"))
+            .bind::<BigInt, _>(migration_version);
+        apply_script(connection, migration.name, migration.down, record)?;
+        reverted.push(migration);
+    }
+    Ok(reverted)
+}
+
+///Retrieve the migrations recorded in the bookkeeping table, ordered by version
+fn get_applied_migrations(
+    connection: &mut SqliteConnection,
+) -> Result<Vec<AppliedMigration>, Error> {
+    Ok(diesel::sql_query(format!(
+        "SELECT version, name FROM {MIGRATIONS_TABLE} ORDER BY version"
+    ))
+    .load(connection)?)
+}
+
+///Check that the migrations recorded in the database are the first ones of the list,
+///returning their number
+fn check_applied_migrations(
+    connection: &mut SqliteConnection,
+    migrations: &[Migration],
+) -> Result<usize, Error> {
+    let applied = get_applied_migrations(connection)?;
+    let compiled = migrations.len() as i64;
+    if let Some(last) = applied.last() {
+        if last.version > compiled {
+            return Err(Error::DatabaseTooRecent {
+                database: last.version,
+                compiled,
+            });
+        }
+    }
+    for (index, record) in applied.iter().enumerate() {
+        let version = index as i64 + 1;
+        if record.version != version || migrations[index].name != record.name {
+            return Err(Error::UnknownMigration {
+                version: record.version,
+                name: record.name.clone(),
+            });
+        }
+    }
+    Ok(applied.len())
+}
+
+///Run a script of a migration and update the bookkeeping table in a transaction, with
+///the foreign keys disabled. The references must be valid once the script is run
+fn apply_script(
+    connection: &mut SqliteConnection,
+    name: &str,
+    script: &str,
+    record: impl ExecuteDsl<SqliteConnection>,
+) -> Result<(), Error> {
+    let failed = |reason: String| Error::MigrationFailed {
+        name: name.to_string(),
+        reason,
+    };
+    //the foreign keys can not be disabled within a transaction
+    let enabled: Count = diesel::sql_query("SELECT foreign_keys AS count FROM pragma_foreign_keys")
+        .get_result(connection)?;
+    connection.batch_execute("PRAGMA foreign_keys = OFF;")?;
+    let result = connection.immediate_transaction(|connection| {
+        connection
+            .batch_execute(script)
+            .map_err(|e| failed(e.to_string()))?;
+        ExecuteDsl::execute(record, connection)?;
+        let broken: Count =
+            diesel::sql_query("SELECT COUNT(*) AS count FROM pragma_foreign_key_check")
+                .get_result(connection)?;
+        match broken.count {
+            0 => Ok(()),
+            count => Err(failed(format!("{count} references are not valid"))),
+        }
+    });
+    let restored = match enabled.count {
+        0 => Ok(()),
+        _ => connection.batch_execute("PRAGMA foreign_keys = ON;"),
+    };
+    result?;
+    Ok(restored?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_database_version, revert_migrations, run_migrations, Migration};
+    use crate::Error;
+
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, SqliteConnection};
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration::new(
+            "install",
+            "CREATE TABLE authors (id INTEGER PRIMARY KEY NOT NULL, version BIGINT NOT NULL);",
+            "DROP TABLE authors;",
+        ),
+        Migration::new(
+            "books",
+            "CREATE TABLE books (
+    id INTEGER PRIMARY KEY NOT NULL,
+    author INTEGER NOT NULL REFERENCES authors(id),
+    version BIGINT NOT NULL
+);",
+            "DROP TABLE books;",
+        ),
+    ];
+
+    #[test]
+    fn migrations() {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        connection
+            .batch_execute("PRAGMA foreign_keys = ON;")
+            .unwrap();
+        assert_eq!(0, get_database_version(&mut connection).unwrap());
+
+        let applied = run_migrations(&mut connection, &MIGRATIONS[..1]).unwrap();
+        assert_eq!(vec![&MIGRATIONS[0]], applied);
+        let applied = run_migrations(&mut connection, MIGRATIONS).unwrap();
+        assert_eq!(vec![&MIGRATIONS[1]], applied);
+        assert!(run_migrations(&mut connection, MIGRATIONS)
+            .unwrap()
+            .is_empty());
+        assert_eq!(2, get_database_version(&mut connection).unwrap());
+
+        //the foreign keys are enabled again
+        connection
+            .batch_execute("INSERT INTO authors VALUES (1, 1); INSERT INTO books VALUES (1, 1, 1);")
+            .unwrap();
+        assert!(connection
+            .batch_execute("INSERT INTO books VALUES (2, 5, 1);")
+            .is_err());
+
+        //an older model can not be used with the database
+        assert!(matches!(
+            run_migrations(&mut connection, &MIGRATIONS[..1]),
+            Err(Error::DatabaseTooRecent {
+                database: 2,
+                compiled: 1
+            })
+        ));
+        let other = [MIGRATIONS[0], Migration::new("other", "", "")];
+        assert!(matches!(
+            run_migrations(&mut connection, &other),
+            Err(Error::UnknownMigration { version: 2, .. })
+        ));
+
+        //a migration breaking the references is rolled back
+        let reverted = revert_migrations(&mut connection, MIGRATIONS, 1).unwrap();
+        assert_eq!(vec![&MIGRATIONS[1]], reverted);
+        let broken = [
+            MIGRATIONS[0],
+            Migration::new(
+                "broken",
+                "CREATE TABLE books (
+    id INTEGER PRIMARY KEY NOT NULL,
+    author INTEGER NOT NULL REFERENCES authors(id)
+);
+INSERT INTO books VALUES (1, 5);",
+                "DROP TABLE books;",
+            ),
+        ];
+        assert!(matches!(
+            run_migrations(&mut connection, &broken),
+            Err(Error::MigrationFailed { .. })
+        ));
+        assert_eq!(1, get_database_version(&mut connection).unwrap());
+        assert!(connection.batch_execute("SELECT * FROM books;").is_err());
+    }
+
+    #[test]
+    fn populated_database() {
+        //the install script as generated for books referencing their author
+        const INSTALL: Migration = Migration::new(
+            "install",
+            "CREATE TABLE authors (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name VARCHAR NOT NULL,
+    version BIGINT NOT NULL
+);
+
+CREATE TABLE books (
+    id INTEGER PRIMARY KEY NOT NULL,
+    author INTEGER NOT NULL,
+    version BIGINT NOT NULL,
+    FOREIGN KEY(author) REFERENCES authors(id)
+);
+
+CREATE INDEX idx_books_author ON books(author);",
+            "DROP TABLE books;\nDROP TABLE authors;",
+        );
+        const SUMMARY: Migration = Migration::new(
+            "summary",
+            "ALTER TABLE books ADD COLUMN summary VARCHAR;",
+            "ALTER TABLE books DROP COLUMN summary;",
+        );
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        connection
+            .batch_execute("PRAGMA foreign_keys = ON;")
+            .unwrap();
+        run_migrations(&mut connection, &[INSTALL]).unwrap();
+        connection
+            .batch_execute(
+                "INSERT INTO authors VALUES (1, 'Ann', 1); INSERT INTO books VALUES (1, 1, 1);",
+            )
+            .unwrap();
+
+        //the valid references do not prevent the next migrations
+        let applied = run_migrations(&mut connection, &[INSTALL, SUMMARY]).unwrap();
+        assert_eq!(vec![&SUMMARY], applied);
+        assert_eq!(2, get_database_version(&mut connection).unwrap());
+    }
+}
@@ -0,0 +1,149 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use diesel::deserialize::FromSql;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::{Sqlite, SqliteValue};
+
+///An universally unique identifier, stored on database as its hyphenated
+///lowercase representation (e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`).
+///
+///As the hyphenated representation has a fixed length, the order of the
+///values on database is the same as the order of the rust values.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    diesel::AsExpression,
+    diesel::FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+pub struct Uuid(uuid::Uuid);
+
+impl Uuid {
+    ///Generate a new random (version 4) identifier
+    pub fn new_v4() -> Uuid {
+        Uuid(uuid::Uuid::new_v4())
+    }
+
+    ///Create an identifier from its 16 bytes
+    pub fn from_bytes(bytes: [u8; 16]) -> Uuid {
+        Uuid(uuid::Uuid::from_bytes(bytes))
+    }
+
+    ///Retrieve the 16 bytes of this identifier
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        self.0.as_bytes()
+    }
+}
+
+impl std::fmt::Display for Uuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.hyphenated())
+    }
+}
+
+///Error returned when a string is not a valid UUID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUuidError(String);
+
+impl std::fmt::Display for ParseUuidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid UUID '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseUuidError {}
+
+impl std::str::FromStr for Uuid {
+    type Err = ParseUuidError;
+
+    ///Parse an identifier. The simple, hyphenated, braced and urn
+    ///representations are accepted
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match uuid::Uuid::parse_str(s) {
+            Ok(v) => Ok(Uuid(v)),
+            Err(_e) => Err(ParseUuidError(s.to_string())),
+        }
+    }
+}
+
+impl serde::Serialize for Uuid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Uuid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse::<Uuid>().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql<Text, Sqlite> for Uuid {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.to_string());
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Sqlite> for Uuid {
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(value.parse::<Uuid>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Uuid;
+
+    #[test]
+    fn round_trip() {
+        let id = Uuid::from_bytes([
+            0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f,
+            0xe0, 0xc8,
+        ]);
+        assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", id.to_string());
+        assert_eq!(Ok(id), "67E55044-10B1-426F-9247-BB680E5FE0C8".parse());
+        assert!("67e55044-10b1".parse::<Uuid>().is_err());
+    }
+
+    #[test]
+    fn order() {
+        let low = Uuid::from_bytes([0x0f; 16]);
+        let high = Uuid::from_bytes([0xa0; 16]);
+        assert!(low < high);
+        assert!(low.to_string() < high.to_string());
+    }
+}
@@ -17,19 +17,48 @@
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
 
+use crate::constraints::AttributeConstraints;
+use crate::model_error::Location;
+
 ///Enumeration used to specify the type of an attribute
 /// in case of a reference, the name of the referenced attribute
-/// is given as parameter.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+/// is given as parameter. In case of an enumeration, the name
+/// of the enumeration is given as parameter. In case of a decimal,
+/// the maximum number of digits and the number of digits after the
+/// decimal point are given as parameters. In case of a typed JSON
+/// document, the full path of the rust type is given as parameter.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub enum BaseAttributeType {
     Integer,
+    Float,
+    Decimal { precision: u32, scale: u32 },
+    Boolean,
     String,
+    Date,
+    Time,
+    Timestamp,
+    Duration,
+    Bytes,
+    Uuid,
+    Json,
+    TypedJson(String),
+    Enum(String),
     Reference(String),
 }
 
+///Enumeration used to specify the type of the primary key of an object
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum IdentifierType {
+    ///An integer, automatically incremented by the database
+    #[default]
+    Integer,
+    ///An UUID, generated when the instance is created
+    Uuid,
+}
+
 ///Enumeration to specify the kind of attribute: mandatory or
 /// optional
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub enum AttributeType {
     Mandatory(BaseAttributeType),
     Optional(BaseAttributeType),
@@ -46,30 +75,155 @@ impl AttributeType {
 }
 
 ///The definition of an attribute
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Attribute {
     /// The name of the attribute
     name: String,
     /// The type of the attribute
     is: AttributeType,
     /// Optional, comments relative to the attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
     comm: Option<String>,
     /// Optional, whether the attribute is mutable or not. Default is false
+    #[serde(skip_serializing_if = "crate::model_format::is_default")]
     mutable: Option<bool>,
-    /// Optional, whether the attribute could be used as index in queries.
-    /// If set to true, the system expect the value to be unique in the column
-    indexable: Option<bool>,
+    /// Optional, whether the values of the attribute are unique in the column.
+    /// A unique attribute can be used to load an instance. Default is false.
+    /// `indexable` is accepted for the models written before the introduction of `indexed`
+    #[serde(alias = "indexable", skip_serializing_if = "crate::model_format::is_default")]
+    unique: Option<bool>,
+    /// Optional, whether the attribute is indexed without being unique, so that
+    /// all the instances having a value can be loaded efficiently. Default is false
+    #[serde(skip_serializing_if = "crate::model_format::is_default")]
+    indexed: Option<bool>,
     /// Optional, whether the attribute should be considered as secret
     /// a secret attribute will not be deserialized
+    #[serde(skip_serializing_if = "crate::model_format::is_default")]
     secret: Option<bool>,
+    /// Optional, the maximum number of bytes a binary attribute may contain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_size: Option<usize>,
+    /// Optional, the value of a mandatory attribute when none is given at creation.
+    /// The value uses the string representation of the attribute (the one of set_attribute)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    /// Optional, the previous name of the attribute, so that renaming it is not seen
+    /// as removing an attribute and adding another one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    renamed_from: Option<String>,
+    /// The constraints on the values of the attribute
+    #[serde(flatten)]
+    constraints: AttributeConstraints,
+    /// The type of the identifier of the referenced object, if any
+    #[serde(skip)]
+    reference_id_type: IdentifierType,
+    /// The place where the attribute is defined in the model
+    #[serde(skip)]
+    location: Location,
 }
 
 impl Attribute {
+    /// Create an attribute of the given type, immutable and without constraints
+    pub fn new(name: &str, is: AttributeType) -> Attribute {
+        Attribute {
+            name: name.to_string(),
+            is,
+            comm: None,
+            mutable: None,
+            unique: None,
+            indexed: None,
+            secret: None,
+            max_size: None,
+            default: None,
+            renamed_from: None,
+            constraints: AttributeConstraints::default(),
+            reference_id_type: IdentifierType::default(),
+            location: Location::default(),
+        }
+    }
+
+    /// Set the comment describing this attribute
+    pub fn comment(mut self, comment: &str) -> Attribute {
+        self.comm = Some(comment.to_string());
+        self
+    }
+
+    /// Make the attribute mutable
+    pub fn mutable(mut self) -> Attribute {
+        self.mutable = Some(true);
+        self
+    }
+
+    /// Make the values of the attribute unique
+    pub fn unique(mut self) -> Attribute {
+        self.unique = Some(true);
+        self
+    }
+
+    /// Index the attribute without making its values unique
+    pub fn indexed(mut self) -> Attribute {
+        self.indexed = Some(true);
+        self
+    }
+
+    /// Make the attribute secret
+    pub fn secret(mut self) -> Attribute {
+        self.secret = Some(true);
+        self
+    }
+
+    /// Set the maximum number of bytes of a binary attribute
+    pub fn max_size(mut self, max_size: usize) -> Attribute {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Set the value of a mandatory attribute when none is given at creation,
+    /// using the string representation of the attribute
+    pub fn default_value(mut self, value: &str) -> Attribute {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    /// Set the previous name of the attribute
+    pub fn renamed_from(mut self, previous_name: &str) -> Attribute {
+        self.renamed_from = Some(previous_name.to_string());
+        self
+    }
+
+    /// Set the constraints on the values of the attribute
+    pub fn constraints(mut self, constraints: AttributeConstraints) -> Attribute {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Retrieve a copy of the attribute with another name
+    pub(crate) fn with_name(&self, name: &str) -> Attribute {
+        let mut attribute = self.clone();
+        attribute.name = name.to_string();
+        attribute
+    }
+
+    /// Retrieve the place where the attribute is defined in the model
+    pub(crate) fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Set the place where the attribute is defined in the model
+    pub(crate) fn set_location(&mut self, location: Location) {
+        self.location = location;
+    }
+
     /// Retrieve the name of the attribute
     pub fn get_name(&self) -> &String {
         &self.name
     }
 
+    /// Retrieve the previous name of the attribute, if it was renamed
+    pub fn get_renamed_from(&self) -> Option<&String> {
+        self.renamed_from.as_ref()
+    }
+
     /// Retrieve the comment describing this attribute
     pub fn get_comment(&self) -> &Option<String> {
         &self.comm
@@ -86,9 +240,15 @@ impl Attribute {
         self.secret.unwrap_or(false)
     }
 
-    /// Check if the attribute can be used as index in a query
-    pub fn is_indexable(&self) -> bool {
-        self.indexable.unwrap_or(false)
+    /// Check if the values of the attribute are unique, and can therefore be
+    /// used to load an instance
+    pub fn is_unique(&self) -> bool {
+        self.unique.unwrap_or(false)
+    }
+
+    /// Check if the attribute has a non unique index
+    pub fn is_indexed(&self) -> bool {
+        self.indexed.unwrap_or(false) && !self.is_unique()
     }
 
     /// Check if the attribute is optional
@@ -107,8 +267,42 @@ impl Attribute {
         }
     }
 
+    /// Retrieve the type of the identifier of the referenced object.
+    /// Only meaningful if the attribute is a reference
+    pub fn get_reference_id_type(&self) -> IdentifierType {
+        self.reference_id_type
+    }
+
+    /// Set the type of the identifier of the referenced object
+    pub(crate) fn set_reference_id_type(&mut self, id_type: IdentifierType) {
+        self.reference_id_type = id_type
+    }
+
+    /// Retrieve the name of the enumeration of the attribute, if any
+    pub fn get_enum(&self) -> Option<&String> {
+        match &self.is.get_base_type() {
+            BaseAttributeType::Enum(e) => Some(e),
+            _ => None,
+        }
+    }
+
     /// Check if the attribute is mutable
     pub fn is_mutable(&self) -> bool {
         self.mutable.unwrap_or(false)
     }
+
+    /// Retrieve the maximum number of bytes of a binary attribute, if any
+    pub fn get_max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// Retrieve the constraints on the values of the attribute
+    pub fn get_constraints(&self) -> &AttributeConstraints {
+        &self.constraints
+    }
+
+    /// Retrieve the default value of the attribute, if any
+    pub fn get_default(&self) -> Option<&String> {
+        self.default.as_ref()
+    }
 }
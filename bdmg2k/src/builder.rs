@@ -0,0 +1,260 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::attributes::{Attribute, IdentifierType};
+use crate::enumeration::Enumeration;
+use crate::mixin::Mixin;
+use crate::model_error::ModelError;
+use crate::object::Object;
+use crate::objectdb::{ObjectDB, RustOutputType};
+
+///The builder defining an object store programmatically, as an alternative to a model file
+/// (e.g. `ObjectDB::builder().object("Book").table("books").attr(title).build()`).
+///The object store is built and validated exactly as if it was loaded
+#[derive(Debug, Default)]
+pub struct ModelBuilder {
+    rust_destination: String,
+    rust_output: Option<RustOutputType>,
+    migrations: Option<String>,
+    objects: Vec<Object>,
+    enums: Vec<Enumeration>,
+    mixins: Vec<Mixin>,
+}
+
+impl ModelBuilder {
+    ///Set the directory in which the code will be generated
+    pub fn destination(mut self, rust_destination: &str) -> ModelBuilder {
+        self.rust_destination = rust_destination.to_string();
+        self
+    }
+
+    ///Set the type of output that must be generated
+    pub fn output(mut self, rust_output: RustOutputType) -> ModelBuilder {
+        self.rust_output = Some(rust_output);
+        self
+    }
+
+    ///Set the directory holding the migrations of the database
+    pub fn migrations(mut self, directory: &str) -> ModelBuilder {
+        self.migrations = Some(directory.to_string());
+        self
+    }
+
+    ///Start the definition of an object. Its table is named after the object until
+    /// another name is given
+    pub fn object(self, name: &str) -> ObjectBuilder {
+        ObjectBuilder {
+            model: self,
+            object: Object::new(name, name),
+        }
+    }
+
+    ///Add an enumeration that can be used as attribute type
+    pub fn enumeration(mut self, enumeration: Enumeration) -> ModelBuilder {
+        self.enums.push(enumeration);
+        self
+    }
+
+    ///Add a mixin that can be included by the objects
+    pub fn mixin(mut self, mixin: Mixin) -> ModelBuilder {
+        self.mixins.push(mixin);
+        self
+    }
+
+    ///Build and validate the object store. All the problems are returned, located by
+    /// their path in the model (e.g. `objects[1].attr[0]`)
+    pub fn build(self) -> Result<ObjectDB, Vec<ModelError>> {
+        let db = ObjectDB::from_parts(
+            self.rust_destination,
+            self.rust_output,
+            self.migrations,
+            self.objects,
+            self.enums,
+            self.mixins,
+        );
+        db.validate()?;
+        Ok(db)
+    }
+}
+
+///The builder of an object, obtained from a model builder. The definition of the object
+/// ends when another element is added to the model or when the model is built
+#[derive(Debug)]
+pub struct ObjectBuilder {
+    model: ModelBuilder,
+    object: Object,
+}
+
+impl ObjectBuilder {
+    ///Set the name of the database table that contain the object
+    pub fn table(mut self, table_name: &str) -> ObjectBuilder {
+        self.object.set_table_name(table_name);
+        self
+    }
+
+    ///Set the type of the primary key of the object
+    pub fn id_type(mut self, id_type: IdentifierType) -> ObjectBuilder {
+        self.object.set_id_type(id_type);
+        self
+    }
+
+    ///Set the description of the object
+    pub fn comment(mut self, description: &str) -> ObjectBuilder {
+        self.object.set_description(description);
+        self
+    }
+
+    ///Set the category in which the object is defined
+    pub fn category(mut self, category: &str) -> ObjectBuilder {
+        self.object.set_category(category);
+        self
+    }
+
+    ///Set the function checking the validity of the instances
+    pub fn validator(mut self, validator: &str) -> ObjectBuilder {
+        self.object.set_validator(validator);
+        self
+    }
+
+    ///Set the previous name of the object, so that the renaming is seen by the differences
+    /// between two versions of the model
+    pub fn renamed_from(mut self, previous_name: &str) -> ObjectBuilder {
+        self.object.set_renamed_from(previous_name);
+        self
+    }
+
+    ///Add an attribute after the attributes of the object
+    pub fn attr(mut self, attribute: Attribute) -> ObjectBuilder {
+        self.object.add_attribute(attribute);
+        self
+    }
+
+    ///Add a group of attributes whose combination of values must be unique
+    pub fn unique(mut self, attribute_names: &[&str]) -> ObjectBuilder {
+        self.object.add_unique_group(
+            attribute_names
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        );
+        self
+    }
+
+    ///Include the attributes of a mixin
+    pub fn mixin(mut self, mixin_name: &str) -> ObjectBuilder {
+        self.object.add_mixin(mixin_name);
+        self
+    }
+
+    ///End the definition of the object, continuing with the model
+    pub fn end(mut self) -> ModelBuilder {
+        self.model.objects.push(self.object);
+        self.model
+    }
+
+    ///End the definition of the object and start the definition of another object
+    pub fn object(self, name: &str) -> ObjectBuilder {
+        self.end().object(name)
+    }
+
+    ///End the definition of the object, then build and validate the object store
+    pub fn build(self) -> Result<ObjectDB, Vec<ModelError>> {
+        self.end().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Attribute, AttributeConstraints, AttributeType, BaseAttributeType, EnumVariant,
+        Enumeration, Mixin,
+    };
+    use crate::{ModelFormat, ObjectDB};
+
+    #[test]
+    fn build() {
+        let db = ObjectDB::builder()
+            .destination("out")
+            .enumeration(
+                Enumeration::new("Genre")
+                    .variant(EnumVariant::new("Novel"))
+                    .variant(EnumVariant::new("Essay")),
+            )
+            .mixin(
+                Mixin::new("Named").attr(
+                    Attribute::new("name", AttributeType::Mandatory(BaseAttributeType::String))
+                        .mutable(),
+                ),
+            )
+            .object("Author")
+            .table("authors")
+            .mixin("Named")
+            .object("Book")
+            .table("books")
+            .comment("A book of the library")
+            .attr(
+                Attribute::new("title", AttributeType::Mandatory(BaseAttributeType::String))
+                    .constraints(AttributeConstraints::default().min_length(1)),
+            )
+            .attr(Attribute::new(
+                "genre",
+                AttributeType::Mandatory(BaseAttributeType::Enum(String::from("Genre"))),
+            ))
+            .attr(Attribute::new(
+                "author",
+                AttributeType::Mandatory(BaseAttributeType::Reference(String::from("Author"))),
+            ))
+            .unique(&["title", "author"])
+            .build()
+            .unwrap();
+        let author = db.get_object("Author").unwrap();
+        assert!(author.is_referenced());
+        assert_eq!(
+            vec!["name"],
+            author
+                .get_attributes()
+                .map(|at| at.get_name())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, db.get_object("Book").unwrap().get_attributes().count());
+
+        //the written model is loaded as the same object store, the attributes of the mixins being written once
+        let content = ModelFormat::Json.write(&db).unwrap();
+        let path = std::env::temp_dir().join(format!("bdmg2k_builder_{}.json", std::process::id()));
+        std::fs::write(&path, &content).unwrap();
+        let loaded = ObjectDB::load(&path).unwrap();
+        assert!(loaded.validate().is_ok());
+        assert_eq!(content, ModelFormat::Json.write(&loaded).unwrap());
+        assert!(loaded.get_object("Author").unwrap().is_referenced());
+        let _ = std::fs::remove_file(&path);
+
+        //the problems are located by their path in the model
+        let errors = ObjectDB::builder()
+            .object("Book")
+            .table("books")
+            .attr(Attribute::new(
+                "author",
+                AttributeType::Mandatory(BaseAttributeType::Reference(String::from("Author"))),
+            ))
+            .build()
+            .unwrap_err();
+        assert_eq!(1, errors.len());
+        assert_eq!("objects[0].attr[0].is", errors[0].get_location().get_path());
+    }
+}
@@ -0,0 +1,254 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::attributes::BaseAttributeType;
+
+///The constraints that the values of an attribute must respect. They are
+/// checked both by the database and by the generated rust code, except for
+/// the pattern which is only checked by the rust code: SQLite does not
+/// provide regular expressions by default. The bounds of a decimal attribute
+/// are only checked by the rust code as well, SQLite comparing them approximately.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Default, Clone)]
+pub struct AttributeConstraints {
+    /// Optional, the minimum value (included) of a numeric, date or time attribute,
+    /// using the string representation of the attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<String>,
+    /// Optional, the maximum value (included) of a numeric, date or time attribute,
+    /// using the string representation of the attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<String>,
+    /// Optional, the minimum number of characters of a string attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_length: Option<usize>,
+    /// Optional, the maximum number of characters of a string attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_length: Option<usize>,
+    /// Optional, the regular expression that the whole value of a string attribute must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    /// Optional, the list of values allowed for a string or integer attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    one_of: Option<Vec<String>>,
+    /// Optional, whether the values of a string attribute are compared without
+    /// taking the (ASCII) case into account. Default is false
+    #[serde(skip_serializing_if = "crate::model_format::is_default")]
+    case_insensitive: Option<bool>,
+}
+
+impl AttributeConstraints {
+    /// Set the minimum value (included), using the string representation of the attribute
+    pub fn min(mut self, min: &str) -> AttributeConstraints {
+        self.min = Some(min.to_string());
+        self
+    }
+
+    /// Set the maximum value (included), using the string representation of the attribute
+    pub fn max(mut self, max: &str) -> AttributeConstraints {
+        self.max = Some(max.to_string());
+        self
+    }
+
+    /// Set the minimum number of characters
+    pub fn min_length(mut self, min_length: usize) -> AttributeConstraints {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    /// Set the maximum number of characters
+    pub fn max_length(mut self, max_length: usize) -> AttributeConstraints {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Set the regular expression that the whole value must match
+    pub fn pattern(mut self, pattern: &str) -> AttributeConstraints {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// Set the list of allowed values
+    pub fn one_of(mut self, values: &[&str]) -> AttributeConstraints {
+        self.one_of = Some(values.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    /// Compare the values without taking the (ASCII) case into account
+    pub fn case_insensitive(mut self) -> AttributeConstraints {
+        self.case_insensitive = Some(true);
+        self
+    }
+
+    /// Retrieve the minimum value, if any
+    pub fn get_min(&self) -> Option<&String> {
+        self.min.as_ref()
+    }
+
+    /// Retrieve the maximum value, if any
+    pub fn get_max(&self) -> Option<&String> {
+        self.max.as_ref()
+    }
+
+    /// Retrieve the minimum number of characters, if any
+    pub fn get_min_length(&self) -> Option<usize> {
+        self.min_length
+    }
+
+    /// Retrieve the maximum number of characters, if any
+    pub fn get_max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Retrieve the regular expression the values must match, if any
+    pub fn get_pattern(&self) -> Option<&String> {
+        self.pattern.as_ref()
+    }
+
+    /// Retrieve the list of allowed values, if any
+    pub fn get_one_of(&self) -> Option<&Vec<String>> {
+        self.one_of.as_ref()
+    }
+
+    /// Check if the values are compared without taking the case into account
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive.unwrap_or(false)
+    }
+
+    /// Check if at least one constraint is defined
+    pub fn is_empty(&self) -> bool {
+        self == &AttributeConstraints::default()
+    }
+
+    /// Check that the constraints can be applied on an attribute of the given type
+    pub fn validate(&self, base_type: &BaseAttributeType) -> Result<(), String> {
+        let is_string = base_type == &BaseAttributeType::String;
+        if self.min.is_some() || self.max.is_some() {
+            let valid_bound = |bound: &String| match base_type {
+                BaseAttributeType::Integer => bound.parse::<i64>().is_ok(),
+                BaseAttributeType::Float => bound.parse::<f64>().is_ok_and(f64::is_finite),
+                BaseAttributeType::Decimal { .. } => matches_pattern(r"-?[0-9]+(\.[0-9]+)?", bound),
+                //the bounds are parsed by the generated code and compared as text by the database
+                BaseAttributeType::Date => {
+                    matches_pattern(r"[0-9]{4}-[0-9]{2}-[0-9]{2}", bound)
+                        && bound.parse::<chrono::NaiveDate>().is_ok()
+                }
+                BaseAttributeType::Time => {
+                    matches_pattern(r"[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?", bound)
+                        && bound.parse::<chrono::NaiveTime>().is_ok()
+                }
+                _ => false,
+            };
+            for bound in self.min.iter().chain(self.max.iter()) {
+                if !valid_bound(bound) {
+                    return Err(format!(
+                        "the bound '{bound}' is not valid: bounds are only allowed on numeric, date and time attributes"
+                    ));
+                }
+            }
+            if let (Some(min), Some(max)) = (&self.min, &self.max) {
+                let inverted = match (min.parse::<f64>(), max.parse::<f64>()) {
+                    (Ok(min), Ok(max)) => min > max,
+                    //dates and times are compared on their representation
+                    _ => min > max,
+                };
+                if inverted {
+                    return Err(format!("the minimum '{min}' exceeds the maximum '{max}'"));
+                }
+            }
+        }
+        if (self.min_length.is_some() || self.max_length.is_some()) && !is_string {
+            return Err(String::from(
+                "lengths are only allowed on string attributes",
+            ));
+        }
+        if let (Some(min), Some(max)) = (self.min_length, self.max_length) {
+            if min > max {
+                return Err(format!(
+                    "the minimum length {min} exceeds the maximum length {max}"
+                ));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            if !is_string {
+                return Err(String::from(
+                    "patterns are only allowed on string attributes",
+                ));
+            }
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(format!("invalid pattern '{pattern}': {e}"));
+            }
+        }
+        if let Some(values) = &self.one_of {
+            let valid = match base_type {
+                BaseAttributeType::String => true,
+                BaseAttributeType::Integer => values.iter().all(|v| v.parse::<i64>().is_ok()),
+                _ => false,
+            };
+            if !valid || values.is_empty() {
+                return Err(String::from(
+                    "the allowed values must be a non empty list of strings or integers",
+                ));
+            }
+        }
+        if self.case_insensitive.is_some() && !is_string {
+            return Err(String::from(
+                "only string attributes can be case insensitive",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Check if the whole value matches the regular expression
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    match regex::Regex::new(&format!("^(?:{pattern})$")) {
+        Ok(r) => r.is_match(value),
+        Err(_e) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AttributeConstraints;
+    use crate::BaseAttributeType;
+
+    #[test]
+    fn validate() {
+        let constraints: AttributeConstraints =
+            serde_json::from_slice(b"{\"min\": \"2020-01-01\", \"max\": \"2020-12-31\"}").unwrap();
+        assert!(constraints.validate(&BaseAttributeType::Date).is_ok());
+        assert!(constraints.validate(&BaseAttributeType::Integer).is_err());
+        let constraints: AttributeConstraints =
+            serde_json::from_slice(b"{\"min\": \"10\", \"max\": \"9.5\"}").unwrap();
+        assert!(constraints.validate(&BaseAttributeType::Float).is_err());
+        let constraints: AttributeConstraints =
+            serde_json::from_slice(b"{\"pattern\": \"[a-z\"}").unwrap();
+        assert!(constraints.validate(&BaseAttributeType::String).is_err());
+        let constraints: AttributeConstraints =
+            serde_json::from_slice(b"{\"one_of\": [\"1\", \"x\"]}").unwrap();
+        assert!(constraints.validate(&BaseAttributeType::String).is_ok());
+        assert!(constraints.validate(&BaseAttributeType::Integer).is_err());
+        assert!(AttributeConstraints::default().is_empty());
+
+        //the dates and times must exist
+        assert!(AttributeConstraints::default().max("2020-02-30").validate(&BaseAttributeType::Date).is_err());
+        assert!(AttributeConstraints::default().min("25:00:00").validate(&BaseAttributeType::Time).is_err());
+        assert!(AttributeConstraints::default().min("12:30:00.5").validate(&BaseAttributeType::Time).is_ok());
+    }
+}
@@ -17,9 +17,10 @@
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
 
+use crate::enumeration::Enumeration;
 use crate::object::Object;
 use crate::objectdb::ObjectDB;
-use crate::{BaseAttributeType, Error};
+use crate::{AttributeConstraints, BaseAttributeType, Error, IdentifierType};
 
 use std::collections::BTreeMap;
 use std::fs::File;
@@ -110,7 +111,21 @@ fn create_category_mapping(object_db: &ObjectDB) -> BTreeMap<String, Vec<&Object
 fn get_base_attr_type(atype: &BaseAttributeType) -> String {
     match atype {
         BaseAttributeType::Integer => String::from("integer"),
+        BaseAttributeType::Float => String::from("float"),
+        BaseAttributeType::Decimal { precision, scale } => format!("decimal({precision}, {scale})"),
+        BaseAttributeType::Boolean => String::from("boolean"),
         BaseAttributeType::String => String::from("string"),
+        BaseAttributeType::Date => String::from("date"),
+        BaseAttributeType::Time => String::from("time"),
+        BaseAttributeType::Timestamp => String::from("timestamp (UTC)"),
+        BaseAttributeType::Duration => String::from("duration"),
+        BaseAttributeType::Bytes => String::from("bytes"),
+        BaseAttributeType::Uuid => String::from("uuid"),
+        BaseAttributeType::Json => String::from("json"),
+        BaseAttributeType::TypedJson(t) => format!("json ({t})"),
+        BaseAttributeType::Enum(other) => {
+            format!("enumeration <a href=\"#{other}\">{other}</a>")
+        }
         BaseAttributeType::Reference(other) => {
             format!(
                 "reference to <a href=\"#{other}\">{other}</a>",
@@ -122,7 +137,7 @@ fn get_base_attr_type(atype: &BaseAttributeType) -> String {
 
 fn get_object_doc(object: &Object) -> String {
     let mut attribute_desc =
-        String::from("<tr><th>Name</th><th>Type</th><th>Explanation</th></tr>\n\n");
+        String::from("<tr><th>Name</th><th>Type</th><th>Default</th><th>Constraints</th><th>Explanation</th></tr>\n\n");
     for at in object.get_attributes() {
         let attribute_comment = match at.get_comment() {
             Some(c) => c.clone(),
@@ -135,8 +150,10 @@ fn get_object_doc(object: &Object) -> String {
             String::new()
         };
 
-        let indexable = if at.is_indexable() {
+        let index = if at.is_unique() {
             String::from("unique ")
+        } else if at.is_indexed() {
+            String::from("indexed ")
         } else {
             String::new()
         };
@@ -153,17 +170,28 @@ fn get_object_doc(object: &Object) -> String {
             String::from("immutable ")
         };
 
+        let max_size = match at.get_max_size() {
+            Some(size) => format!(" (at most {size} bytes)"),
+            None => String::new(),
+        };
+
         let atype = format!(
-            "{secret}{unique}{optional}{immutable}{base_type}",
+            "{secret}{unique}{optional}{immutable}{base_type}{max_size}",
             secret = secret,
-            unique = indexable,
+            unique = index,
             optional = optional,
             immutable = immutable,
             base_type = get_base_attr_type(at.get_type().get_base_type())
         );
 
+        let default = match at.get_default() {
+            Some(d) => format!("<code>{d}</code>"),
+            None => String::new(),
+        };
+
         attribute_desc = format!(
-            "{desc}\n<tr><td>{name}</td><td>{atype}</td><td>{expl}</td></tr>",
+            "{desc}\n<tr><td>{name}</td><td>{atype}</td><td>{default}</td><td>{constraints}</td><td>{expl}</td></tr>",
+            constraints = get_constraints_doc(at.get_constraints()),
             desc = attribute_desc,
             name = at.get_name(),
             expl = attribute_comment,
@@ -175,14 +203,79 @@ fn get_object_doc(object: &Object) -> String {
         Some(d) => d.clone(),
         None => String::new(),
     };
+    let identifier = match object.get_id_type() {
+        IdentifierType::Integer => "",
+        IdentifierType::Uuid => "\n\n*identifier*: uuid",
+    };
+    let mut uniques = String::new();
+    for group in object.get_unique_groups() {
+        uniques = format!("{uniques}\n\n*unique*: ({})", group.join(", "));
+    }
+    let mixins: Vec<&str> = object.get_mixins().map(String::as_str).collect();
+    let mixins = if mixins.is_empty() {
+        String::new()
+    } else {
+        format!("\n\n*mixins*: {}", mixins.join(", "))
+    };
+    let hierarchy = match object.get_hierarchy_attribute() {
+        Some(parent) => format!("\n\n*hierarchy*: parent given by {}", parent.get_name()),
+        None => String::new(),
+    };
     format!(
-        "{desc}\n\n*table name*: {table_name}\n\n<table>{attributes}\n</table>",
+        "{desc}\n\n*table name*: {table_name}{identifier}{mixins}{uniques}{hierarchy}\n\n<table>{attributes}\n</table>",
         desc = desc,
         table_name = object.get_table_name(),
         attributes = attribute_desc
     )
 }
 
+fn get_constraints_doc(constraints: &AttributeConstraints) -> String {
+    let mut descriptions = Vec::new();
+    if let Some(min) = constraints.get_min() {
+        descriptions.push(format!("at least <code>{min}</code>"));
+    }
+    if let Some(max) = constraints.get_max() {
+        descriptions.push(format!("at most <code>{max}</code>"));
+    }
+    match (constraints.get_min_length(), constraints.get_max_length()) {
+        (Some(min), Some(max)) => descriptions.push(format!("{min} to {max} characters")),
+        (Some(min), None) => descriptions.push(format!("at least {min} characters")),
+        (None, Some(max)) => descriptions.push(format!("at most {max} characters")),
+        (None, None) => {}
+    }
+    if let Some(pattern) = constraints.get_pattern() {
+        descriptions.push(format!("matches <code>{pattern}</code>"));
+    }
+    if let Some(values) = constraints.get_one_of() {
+        let values: Vec<String> = values.iter().map(|v| format!("<code>{v}</code>")).collect();
+        descriptions.push(format!("one of {}", values.join(", ")));
+    }
+    if constraints.is_case_insensitive() {
+        descriptions.push(String::from("case insensitive"));
+    }
+    descriptions.join(", ")
+}
+
+fn get_enum_doc(enumeration: &Enumeration) -> String {
+    let mut variant_desc = String::from("<tr><th>Value</th><th>Explanation</th></tr>\n\n");
+    for variant in enumeration.get_variants() {
+        variant_desc = format!(
+            "{variant_desc}\n<tr><td>{name}</td><td>{expl}</td></tr>",
+            name = variant.get_name(),
+            expl = match variant.get_comment() {
+                Some(c) => c.clone(),
+                None => String::new(),
+            }
+        );
+    }
+
+    let desc = match enumeration.get_comment() {
+        Some(d) => d.clone(),
+        None => String::new(),
+    };
+    format!("{desc}\n\n<table>{variant_desc}\n</table>")
+}
+
 fn get_dot_node_arcs(object: &Object) -> (String, String) {
     //if an object is a relation but is referenced anywhere, then we must draw it nevertheless
     match (object.is_referenced(), object.is_object_relation()) {
@@ -320,6 +413,30 @@ pub fn write_doc(object_db: &ObjectDB, destination: &str, doc_name: &str) -> Res
         cat_index += 1;
     }
 
+    if object_db.get_enums().len() > 0 {
+        toc = format!("{toc}\n{index}. [Enumerations](#Enumerations)", index = cat_index);
+        content = format!(
+            "{content}\n\n<a name=\"Enumerations\"></a>\n##{index}. Enumerations",
+            index = cat_index
+        );
+        let mut enums: Vec<&Enumeration> = object_db.get_enums().collect();
+        enums.sort_by(|enum_a, enum_b| enum_a.get_name().cmp(enum_b.get_name()));
+        for (enum_index, enumeration) in enums.iter().enumerate() {
+            toc = format!(
+                "{toc}\n    {index}. [{enum_name}](#{enum_name})",
+                index = enum_index + 1,
+                enum_name = enumeration.get_name()
+            );
+            content = format!(
+                "{content}\n\n<a name=\"{enum_name}\"></a>\n##{cat_index}.{index}. {enum_name}\n{enum_doc}",
+                index = enum_index + 1,
+                enum_name = enumeration.get_name(),
+                enum_doc = get_enum_doc(enumeration),
+            );
+        }
+        cat_index += 1;
+    }
+
     let diagram = format!("<a name=\"Diagram\"></a>\n## Diagram\n\nThe following diagram shows the relation between the different element of the data model.\n\n![Data model diagram](./{doc_name}.svg)", doc_name = doc_name);
     content = format!("{}\n{}", content, diagram);
     toc = format!(
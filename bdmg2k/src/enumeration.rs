@@ -0,0 +1,161 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::model_error::{check_identifier, Location, ModelError, ModelErrorKind};
+
+use std::collections::HashSet;
+
+///The definition of a value that an enumeration can take
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub struct EnumVariant {
+    /// The name of the variant, used both in rust and on database
+    name: String,
+    /// Optional, comments relative to the variant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comm: Option<String>,
+}
+
+impl EnumVariant {
+    /// Create a variant without comment
+    pub fn new(name: &str) -> EnumVariant {
+        EnumVariant {
+            name: name.to_string(),
+            comm: None,
+        }
+    }
+
+    /// Set the comment describing this variant
+    pub fn comment(mut self, comment: &str) -> EnumVariant {
+        self.comm = Some(comment.to_string());
+        self
+    }
+
+    /// Retrieve the name of the variant
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    /// Retrieve the comment describing this variant
+    pub fn get_comment(&self) -> &Option<String> {
+        &self.comm
+    }
+}
+
+///The definition of an enumeration: a closed set of values that an
+/// attribute can take
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub struct Enumeration {
+    /// The name of the enumeration
+    name: String,
+    /// Optional, comments relative to the enumeration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comm: Option<String>,
+    /// The list of values of the enumeration
+    variants: Vec<EnumVariant>,
+    /// The place where the enumeration is defined in the model
+    #[serde(skip)]
+    location: Location,
+}
+
+impl Enumeration {
+    /// Create an enumeration without variants
+    pub fn new(name: &str) -> Enumeration {
+        Enumeration {
+            name: name.to_string(),
+            comm: None,
+            variants: Vec::new(),
+            location: Location::default(),
+        }
+    }
+
+    /// Set the comment describing this enumeration
+    pub fn comment(mut self, comment: &str) -> Enumeration {
+        self.comm = Some(comment.to_string());
+        self
+    }
+
+    /// Add a variant after the variants of this enumeration
+    pub fn variant(mut self, variant: EnumVariant) -> Enumeration {
+        self.variants.push(variant);
+        self
+    }
+
+    /// Retrieve the name of the enumeration
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    /// Retrieve the comment describing this enumeration
+    pub fn get_comment(&self) -> &Option<String> {
+        &self.comm
+    }
+
+    /// Retrieve an iterator to the variants of this enumeration
+    pub fn get_variants(&self) -> std::slice::Iter<'_, EnumVariant> {
+        self.variants.iter()
+    }
+
+    /// Retrieve the place where the enumeration is defined in the model
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Set the place where the enumeration is defined in the model
+    pub(crate) fn set_location(&mut self, location: Location) {
+        self.location = location;
+    }
+
+    /// Check that the enumeration has at least one variant and that the
+    /// variant names are unique and valid identifiers. All the problems are returned
+    pub fn validate(&self) -> Vec<ModelError> {
+        let mut errors = Vec::new();
+        errors.extend(check_identifier(self.get_name(), "enumeration name", &self.location.child("name")));
+        if self.variants.is_empty() {
+            errors.push(
+                ModelError::new(
+                    ModelErrorKind::InvalidDefinition,
+                    format!(
+                        "The enumeration '{enum_name}' does not define any variant",
+                        enum_name = self.get_name()
+                    ),
+                )
+                .at(&self.location),
+            );
+        }
+        let mut names = HashSet::with_capacity(self.variants.len());
+        for (index, variant) in self.get_variants().enumerate() {
+            let location = self.location.item("variants", index);
+            errors.extend(check_identifier(variant.get_name(), "variant name", &location.child("name")));
+            if !names.insert(variant.get_name()) {
+                errors.push(
+                    ModelError::new(
+                        ModelErrorKind::DuplicateName,
+                        format!(
+                            "The variant '{variant_name}' is defined twice in the enumeration '{enum_name}'",
+                            variant_name = variant.get_name(),
+                            enum_name = self.get_name()
+                        ),
+                    )
+                    .at(&location),
+                );
+            }
+        }
+        errors
+    }
+}
@@ -23,6 +23,13 @@ extern crate serde_derive;
 extern crate serde_json;
 
 mod attributes;
+mod builder;
+mod constraints;
+mod enumeration;
+mod mixin;
+mod model_diff;
+mod model_error;
+mod model_format;
 mod object;
 mod objectdb;
 
@@ -31,8 +38,15 @@ pub mod rust_generator;
 pub mod sqlite_generator;
 
 pub use attributes::*;
+pub use builder::{ModelBuilder, ObjectBuilder};
+pub use constraints::AttributeConstraints;
+pub use enumeration::{EnumVariant, Enumeration};
+pub use mixin::Mixin;
+pub use model_diff::{ChangeKind, Compatibility, ModelChange, ModelDiff};
+pub use model_error::{Location, ModelError, ModelErrorKind};
+pub use model_format::{canonical_model, check_model_file, convert_model_file, format_model_file, ModelFormat};
 pub use object::Object;
-pub use objectdb::ObjectDB;
+pub use objectdb::{ObjectDB, RustOutputType};
 
 #[derive(Debug)]
 pub enum Error {
@@ -46,6 +60,10 @@ pub enum Error {
     UnableToCreateFile {
         file: String,
     },
+    UnableToReadFile {
+        file: String,
+        error: std::io::Error,
+    },
     UnableToWriteToFile {
         file: String,
         content: String,
@@ -53,6 +71,10 @@ pub enum Error {
     UnableToWriteCodeForObject {
         object_name: String,
     },
+    UnsupportedMigration {
+        object: String,
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for Error {
@@ -61,8 +83,10 @@ impl std::fmt::Display for Error {
             Error::UnableToCreateOutputDirectory { destination, error } => writeln!(f, "Unable to create the directory '{destination}': Error: {error}"),
             Error::DestinationIsNotDirectory { destination } => writeln!(f, "The destination '{destination}' is not a directory."),
             Error::UnableToCreateFile { file } => writeln!(f, "Unable to create the file '{file}'."),
+            Error::UnableToReadFile { file, error } => writeln!(f, "Unable to read the file '{file}': Error: {error}"),
             Error::UnableToWriteToFile { file, content } => writeln!(f, "Unable to write to the file '{file}': >>>{}", content.replace("\n", "\n>>>")),
             Error::UnableToWriteCodeForObject { object_name } => writeln!(f, "Unable to create the code for the object '{object_name}'."),
+            Error::UnsupportedMigration { object, reason } => writeln!(f, "Unable to migrate the table of the object '{object}': {reason}."),
         }
     }
 }
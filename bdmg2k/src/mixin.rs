@@ -0,0 +1,128 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::attributes::Attribute;
+use crate::model_error::{check_identifier, Location, ModelError, ModelErrorKind};
+
+use std::collections::HashSet;
+
+///The definition of a set of attributes shared by multiple objects. The
+/// attributes are added to every object including the mixin, and a rust
+/// trait giving access to them is implemented by these objects
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub struct Mixin {
+    /// The name of the mixin, used as name of the rust trait
+    name: String,
+    /// Optional, comments relative to the mixin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comm: Option<String>,
+    /// The attributes shared by the objects including the mixin
+    attr: Vec<Attribute>,
+    /// The place where the mixin is defined in the model
+    #[serde(skip)]
+    location: Location,
+}
+
+impl Mixin {
+    /// Create a mixin without attributes
+    pub fn new(name: &str) -> Mixin {
+        Mixin {
+            name: name.to_string(),
+            comm: None,
+            attr: Vec::new(),
+            location: Location::default(),
+        }
+    }
+
+    /// Set the comment describing this mixin
+    pub fn comment(mut self, comment: &str) -> Mixin {
+        self.comm = Some(comment.to_string());
+        self
+    }
+
+    /// Add an attribute after the attributes of this mixin
+    pub fn attr(mut self, attribute: Attribute) -> Mixin {
+        self.attr.push(attribute);
+        self
+    }
+
+    /// Retrieve the name of the mixin
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    /// Retrieve the comment describing this mixin
+    pub fn get_comment(&self) -> &Option<String> {
+        &self.comm
+    }
+
+    /// Retrieve an iterator to the attributes of this mixin
+    pub fn get_attributes(&self) -> std::slice::Iter<'_, Attribute> {
+        self.attr.iter()
+    }
+
+    /// Retrieve the place where the mixin is defined in the model
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Set the place where the mixin and its attributes are defined in the model
+    pub(crate) fn set_location(&mut self, location: Location) {
+        for (index, at) in self.attr.iter_mut().enumerate() {
+            at.set_location(location.item("attr", index));
+        }
+        self.location = location;
+    }
+
+    /// Check that the mixin has at least one attribute and that the
+    /// attribute names are unique. All the problems are returned
+    pub fn validate(&self) -> Vec<ModelError> {
+        let mut errors = Vec::new();
+        errors.extend(check_identifier(self.get_name(), "mixin name", &self.location.child("name")));
+        if self.attr.is_empty() {
+            errors.push(
+                ModelError::new(
+                    ModelErrorKind::InvalidDefinition,
+                    format!(
+                        "The mixin '{mixin_name}' does not define any attribute",
+                        mixin_name = self.get_name()
+                    ),
+                )
+                .at(&self.location),
+            );
+        }
+        let mut names = HashSet::with_capacity(self.attr.len());
+        for at in self.get_attributes() {
+            if !names.insert(at.get_name()) {
+                errors.push(
+                    ModelError::new(
+                        ModelErrorKind::DuplicateName,
+                        format!(
+                            "The attribute '{attribute_name}' is defined twice in the mixin '{mixin_name}'",
+                            attribute_name = at.get_name(),
+                            mixin_name = self.get_name()
+                        ),
+                    )
+                    .at(at.get_location()),
+                );
+            }
+        }
+        errors
+    }
+}
@@ -0,0 +1,851 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::attributes::{Attribute, BaseAttributeType, IdentifierType};
+use crate::enumeration::Enumeration;
+use crate::object::Object;
+use crate::objectdb::ObjectDB;
+
+use self::Compatibility::{Breaking, Compatible};
+
+///Whether a change can be applied without breaking the existing data or code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compatibility {
+    ///The existing data stays valid, or the existing code still compiles and behaves the same
+    Compatible,
+    ///The existing data may be lost or become invalid, or the existing code must be adapted
+    Breaking,
+}
+
+///A semantic change between two versions of a model. The objects and the attributes
+/// are designated by their name in the new version, except for the removed ones
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    ObjectAdded {
+        object: String,
+    },
+    ObjectRemoved {
+        object: String,
+    },
+    ObjectRenamed {
+        from: String,
+        to: String,
+    },
+    TableRenamed {
+        object: String,
+        from: String,
+        to: String,
+    },
+    IdentifierTypeChanged {
+        object: String,
+        from: IdentifierType,
+        to: IdentifierType,
+    },
+    AttributeAdded {
+        object: String,
+        attribute: String,
+    },
+    AttributeRemoved {
+        object: String,
+        attribute: String,
+    },
+    AttributeRenamed {
+        object: String,
+        from: String,
+        to: String,
+    },
+    TypeChanged {
+        object: String,
+        attribute: String,
+        from: BaseAttributeType,
+        to: BaseAttributeType,
+    },
+    ReferenceChanged {
+        object: String,
+        attribute: String,
+        from: String,
+        to: String,
+    },
+    OptionalityChanged {
+        object: String,
+        attribute: String,
+        optional: bool,
+    },
+    MutabilityChanged {
+        object: String,
+        attribute: String,
+        mutable: bool,
+    },
+    UniquenessChanged {
+        object: String,
+        attribute: String,
+        unique: bool,
+    },
+    IndexChanged {
+        object: String,
+        attribute: String,
+        indexed: bool,
+    },
+    EnumAdded {
+        enumeration: String,
+    },
+    EnumRemoved {
+        enumeration: String,
+    },
+    VariantAdded {
+        enumeration: String,
+        variant: String,
+    },
+    VariantRemoved {
+        enumeration: String,
+        variant: String,
+    },
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::ObjectAdded { object } => write!(f, "The object '{object}' is added"),
+            ChangeKind::ObjectRemoved { object } => write!(f, "The object '{object}' is removed"),
+            ChangeKind::ObjectRenamed { from, to } => {
+                write!(f, "The object '{from}' is renamed '{to}'")
+            }
+            ChangeKind::TableRenamed { object, from, to } => {
+                write!(
+                    f,
+                    "The table of '{object}' is renamed from '{from}' to '{to}'"
+                )
+            }
+            ChangeKind::IdentifierTypeChanged { object, from, to } => {
+                write!(
+                    f,
+                    "The identifier of '{object}' changes from {from:?} to {to:?}"
+                )
+            }
+            ChangeKind::AttributeAdded { object, attribute } => {
+                write!(f, "The attribute '{object}.{attribute}' is added")
+            }
+            ChangeKind::AttributeRemoved { object, attribute } => {
+                write!(f, "The attribute '{object}.{attribute}' is removed")
+            }
+            ChangeKind::AttributeRenamed { object, from, to } => {
+                write!(f, "The attribute '{object}.{from}' is renamed '{to}'")
+            }
+            ChangeKind::TypeChanged {
+                object,
+                attribute,
+                from,
+                to,
+            } => {
+                write!(
+                    f,
+                    "The type of '{object}.{attribute}' changes from {from:?} to {to:?}"
+                )
+            }
+            ChangeKind::ReferenceChanged {
+                object,
+                attribute,
+                from,
+                to,
+            } => {
+                write!(
+                    f,
+                    "The attribute '{object}.{attribute}' references '{to}' instead of '{from}'"
+                )
+            }
+            ChangeKind::OptionalityChanged {
+                object,
+                attribute,
+                optional,
+            } => match optional {
+                true => write!(f, "The attribute '{object}.{attribute}' becomes optional"),
+                false => write!(f, "The attribute '{object}.{attribute}' becomes mandatory"),
+            },
+            ChangeKind::MutabilityChanged {
+                object,
+                attribute,
+                mutable,
+            } => match mutable {
+                true => write!(f, "The attribute '{object}.{attribute}' becomes mutable"),
+                false => write!(f, "The attribute '{object}.{attribute}' becomes immutable"),
+            },
+            ChangeKind::UniquenessChanged {
+                object,
+                attribute,
+                unique,
+            } => match unique {
+                true => write!(f, "The attribute '{object}.{attribute}' becomes unique"),
+                false => write!(
+                    f,
+                    "The attribute '{object}.{attribute}' is no longer unique"
+                ),
+            },
+            ChangeKind::IndexChanged {
+                object,
+                attribute,
+                indexed,
+            } => match indexed {
+                true => write!(f, "The attribute '{object}.{attribute}' becomes indexed"),
+                false => write!(
+                    f,
+                    "The attribute '{object}.{attribute}' is no longer indexed"
+                ),
+            },
+            ChangeKind::EnumAdded { enumeration } => {
+                write!(f, "The enumeration '{enumeration}' is added")
+            }
+            ChangeKind::EnumRemoved { enumeration } => {
+                write!(f, "The enumeration '{enumeration}' is removed")
+            }
+            ChangeKind::VariantAdded {
+                enumeration,
+                variant,
+            } => {
+                write!(f, "The variant '{enumeration}::{variant}' is added")
+            }
+            ChangeKind::VariantRemoved {
+                enumeration,
+                variant,
+            } => {
+                write!(f, "The variant '{enumeration}::{variant}' is removed")
+            }
+        }
+    }
+}
+
+///A change between two versions of a model, with its impact on the existing databases
+/// and on the code calling the generated rust code
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelChange {
+    kind: ChangeKind,
+    database: Compatibility,
+    code: Compatibility,
+}
+
+impl ModelChange {
+    ///Retrieve the description of the change
+    pub fn get_kind(&self) -> &ChangeKind {
+        &self.kind
+    }
+
+    ///Retrieve whether the data of the existing databases stays valid
+    pub fn get_database_compatibility(&self) -> Compatibility {
+        self.database
+    }
+
+    ///Retrieve whether the code calling the generated rust code still compiles and behaves the same
+    pub fn get_code_compatibility(&self) -> Compatibility {
+        self.code
+    }
+
+    ///Check if the change is breaking, either for the databases or for the code
+    pub fn is_breaking(&self) -> bool {
+        self.database == Compatibility::Breaking || self.code == Compatibility::Breaking
+    }
+}
+
+impl std::fmt::Display for ModelChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let impact = |compatibility: Compatibility| match compatibility {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "breaking",
+        };
+        write!(
+            f,
+            "{} (database: {}, code: {})",
+            self.kind,
+            impact(self.database),
+            impact(self.code)
+        )
+    }
+}
+
+///The changes between two versions of a model
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelDiff {
+    changes: Vec<ModelChange>,
+}
+
+impl ModelDiff {
+    ///Retrieve an iterator to the changes, ordered as the elements of the models
+    pub fn get_changes(&self) -> std::slice::Iter<'_, ModelChange> {
+        self.changes.iter()
+    }
+
+    ///Check if the models are the same, as far as the compared properties are concerned
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    ///Check if at least one change may lose or invalidate the data of the existing databases
+    pub fn is_breaking_database(&self) -> bool {
+        self.get_changes()
+            .any(|c| c.database == Compatibility::Breaking)
+    }
+
+    ///Check if at least one change requires the code calling the generated rust code to be adapted
+    pub fn is_breaking_code(&self) -> bool {
+        self.get_changes()
+            .any(|c| c.code == Compatibility::Breaking)
+    }
+
+    fn push(&mut self, kind: ChangeKind, database: Compatibility, code: Compatibility) {
+        self.changes.push(ModelChange {
+            kind,
+            database,
+            code,
+        });
+    }
+}
+
+///Match the objects of two versions of a model. An object is matched with its previous
+/// version by the name it was renamed from, then by its name, then by its table.
+///An old object is matched at most once, and the objects explicitly renamed are only
+/// matched by their renaming.
+///The pairs are ordered as the new objects, followed by the removed objects
+pub(crate) fn match_objects<'a>(
+    old: &'a ObjectDB,
+    new: &'a ObjectDB,
+) -> Vec<(Option<&'a Object>, Option<&'a Object>)> {
+    let renamed: Vec<&String> = new.get_objects().filter_map(|o| o.get_renamed_from()).collect();
+    let mut pairs = Vec::new();
+    let mut matched: Vec<&String> = Vec::new();
+    for new_object in new.get_objects() {
+        let old_object = new_object
+            .get_renamed_from()
+            .and_then(|name| old.get_object(name))
+            .filter(|o| !matched.contains(&o.get_name()))
+            .or_else(|| {
+                old.get_object(new_object.get_name())
+                    .filter(|o| !renamed.contains(&o.get_name()) && !matched.contains(&o.get_name()))
+            })
+            .or_else(|| {
+                old.get_objects().find(|o| {
+                    o.get_table_name() == new_object.get_table_name()
+                        && new.get_object(o.get_name()).is_none()
+                        && !renamed.contains(&o.get_name())
+                        && !matched.contains(&o.get_name())
+                })
+            });
+        if let Some(old_object) = old_object {
+            matched.push(old_object.get_name());
+        }
+        pairs.push((old_object, Some(new_object)));
+    }
+    for old_object in old.get_objects() {
+        if !matched.contains(&old_object.get_name()) {
+            pairs.push((Some(old_object), None));
+        }
+    }
+    pairs
+}
+
+///Match the attributes of two versions of an object. An attribute is matched with its
+/// previous version by the name it was renamed from, then by its name. An old attribute is
+/// matched at most once, and the attributes explicitly renamed are only matched by their renaming.
+///The pairs are ordered as the new attributes, followed by the removed attributes
+pub(crate) fn match_attributes<'a>(
+    old: &'a Object,
+    new: &'a Object,
+) -> Vec<(Option<&'a Attribute>, Option<&'a Attribute>)> {
+    let find = |name: &String| old.get_attributes().find(|at| at.get_name() == name);
+    let renamed: Vec<&String> = new.get_attributes().filter_map(|at| at.get_renamed_from()).collect();
+    let mut pairs = Vec::new();
+    let mut matched: Vec<&String> = Vec::new();
+    for new_attribute in new.get_attributes() {
+        let old_attribute = new_attribute
+            .get_renamed_from()
+            .and_then(find)
+            .filter(|at| !matched.contains(&at.get_name()))
+            .or_else(|| {
+                find(new_attribute.get_name())
+                    .filter(|at| !renamed.contains(&at.get_name()) && !matched.contains(&at.get_name()))
+            });
+        if let Some(old_attribute) = old_attribute {
+            matched.push(old_attribute.get_name());
+        }
+        pairs.push((old_attribute, Some(new_attribute)));
+    }
+    for old_attribute in old.get_attributes() {
+        if !matched.contains(&old_attribute.get_name()) {
+            pairs.push((Some(old_attribute), None));
+        }
+    }
+    pairs
+}
+
+///Compute the changes between two versions of a model
+pub(crate) fn diff_models(old: &ObjectDB, new: &ObjectDB) -> ModelDiff {
+    let mut diff = ModelDiff::default();
+    for pair in match_objects(old, new) {
+        match pair {
+            (Some(old_object), Some(new_object)) => diff_objects(&mut diff, old_object, new_object),
+            (None, Some(new_object)) => diff.push(
+                ChangeKind::ObjectAdded {
+                    object: new_object.get_name().clone(),
+                },
+                Compatible,
+                Compatible,
+            ),
+            (Some(old_object), None) => diff.push(
+                ChangeKind::ObjectRemoved {
+                    object: old_object.get_name().clone(),
+                },
+                Breaking,
+                Breaking,
+            ),
+            (None, None) => {}
+        }
+    }
+
+    for new_enum in new.get_enums() {
+        match old.get_enum(new_enum.get_name()) {
+            Some(old_enum) => diff_enums(&mut diff, old_enum, new_enum),
+            None => diff.push(
+                ChangeKind::EnumAdded {
+                    enumeration: new_enum.get_name().clone(),
+                },
+                Compatible,
+                Compatible,
+            ),
+        }
+    }
+    for old_enum in old.get_enums() {
+        if new.get_enum(old_enum.get_name()).is_none() {
+            //the attributes using the enumeration are reported by themselves
+            diff.push(
+                ChangeKind::EnumRemoved {
+                    enumeration: old_enum.get_name().clone(),
+                },
+                Compatible,
+                Breaking,
+            );
+        }
+    }
+    diff
+}
+
+///Add the changes between two versions of an object
+fn diff_objects(diff: &mut ModelDiff, old: &Object, new: &Object) {
+    let object = new.get_name().clone();
+    if old.get_name() != new.get_name() {
+        //the generated type is renamed, while the data stays where it is
+        diff.push(
+            ChangeKind::ObjectRenamed {
+                from: old.get_name().clone(),
+                to: object.clone(),
+            },
+            Compatible,
+            Breaking,
+        );
+    }
+    if old.get_table_name() != new.get_table_name() {
+        diff.push(
+            ChangeKind::TableRenamed {
+                object: object.clone(),
+                from: old.get_table_name().clone(),
+                to: new.get_table_name().clone(),
+            },
+            Compatible,
+            Compatible,
+        );
+    }
+    if old.get_id_type() != new.get_id_type() {
+        diff.push(
+            ChangeKind::IdentifierTypeChanged {
+                object: object.clone(),
+                from: old.get_id_type(),
+                to: new.get_id_type(),
+            },
+            Breaking,
+            Breaking,
+        );
+    }
+
+    for pair in match_attributes(old, new) {
+        match pair {
+            (Some(old_attribute), Some(new_attribute)) => {
+                diff_attributes(diff, &object, old_attribute, new_attribute)
+            }
+            //every attribute is a parameter of the constructor, and the existing rows
+            //need a value for the mandatory attributes
+            (None, Some(new_attribute)) => {
+                let database =
+                    if new_attribute.is_optional() || new_attribute.get_default().is_some() {
+                        Compatible
+                    } else {
+                        Breaking
+                    };
+                diff.push(
+                    ChangeKind::AttributeAdded {
+                        object: object.clone(),
+                        attribute: new_attribute.get_name().clone(),
+                    },
+                    database,
+                    Breaking,
+                );
+            }
+            (Some(old_attribute), None) => diff.push(
+                ChangeKind::AttributeRemoved {
+                    object: object.clone(),
+                    attribute: old_attribute.get_name().clone(),
+                },
+                Breaking,
+                Breaking,
+            ),
+            (None, None) => {}
+        }
+    }
+}
+
+///Add the changes between two versions of an attribute
+fn diff_attributes(diff: &mut ModelDiff, object: &str, old: &Attribute, new: &Attribute) {
+    let attribute = new.get_name().clone();
+    if old.get_name() != new.get_name() {
+        diff.push(
+            ChangeKind::AttributeRenamed {
+                object: object.to_string(),
+                from: old.get_name().clone(),
+                to: attribute.clone(),
+            },
+            Compatible,
+            Breaking,
+        );
+    }
+    let (old_type, new_type) = (
+        old.get_type().get_base_type(),
+        new.get_type().get_base_type(),
+    );
+    match (old.get_reference(), new.get_reference()) {
+        (Some(from), Some(to)) if from != to => diff.push(
+            ChangeKind::ReferenceChanged {
+                object: object.to_string(),
+                attribute: attribute.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            },
+            Breaking,
+            Breaking,
+        ),
+        _ if old_type != new_type => diff.push(
+            ChangeKind::TypeChanged {
+                object: object.to_string(),
+                attribute: attribute.clone(),
+                from: old_type.clone(),
+                to: new_type.clone(),
+            },
+            Breaking,
+            Breaking,
+        ),
+        _ => {}
+    }
+    if old.is_optional() != new.is_optional() {
+        //the existing rows may not have a value
+        let database = if new.is_optional() {
+            Compatible
+        } else {
+            Breaking
+        };
+        diff.push(
+            ChangeKind::OptionalityChanged {
+                object: object.to_string(),
+                attribute: attribute.clone(),
+                optional: new.is_optional(),
+            },
+            database,
+            Breaking,
+        );
+    }
+    if old.is_mutable() != new.is_mutable() {
+        //a setter is added or removed
+        let code = if new.is_mutable() {
+            Compatible
+        } else {
+            Breaking
+        };
+        diff.push(
+            ChangeKind::MutabilityChanged {
+                object: object.to_string(),
+                attribute: attribute.clone(),
+                mutable: new.is_mutable(),
+            },
+            Compatible,
+            code,
+        );
+    }
+    if old.is_unique() != new.is_unique() {
+        //the existing rows may contain duplicates, and a loader is added or removed
+        let (database, code) = if new.is_unique() {
+            (Breaking, Compatible)
+        } else {
+            (Compatible, Breaking)
+        };
+        diff.push(
+            ChangeKind::UniquenessChanged {
+                object: object.to_string(),
+                attribute: attribute.clone(),
+                unique: new.is_unique(),
+            },
+            database,
+            code,
+        );
+    }
+    if old.is_indexed() != new.is_indexed() {
+        let code = if new.is_indexed() {
+            Compatible
+        } else {
+            Breaking
+        };
+        diff.push(
+            ChangeKind::IndexChanged {
+                object: object.to_string(),
+                attribute,
+                indexed: new.is_indexed(),
+            },
+            Compatible,
+            code,
+        );
+    }
+}
+
+///Add the changes between two versions of an enumeration. Adding a variant breaks the
+/// exhaustive matches on the generated enumeration
+fn diff_enums(diff: &mut ModelDiff, old: &Enumeration, new: &Enumeration) {
+    let enumeration = new.get_name();
+    for variant in new.get_variants() {
+        if !old
+            .get_variants()
+            .any(|v| v.get_name() == variant.get_name())
+        {
+            diff.push(
+                ChangeKind::VariantAdded {
+                    enumeration: enumeration.clone(),
+                    variant: variant.get_name().clone(),
+                },
+                Compatible,
+                Breaking,
+            );
+        }
+    }
+    for variant in old.get_variants() {
+        if !new
+            .get_variants()
+            .any(|v| v.get_name() == variant.get_name())
+        {
+            diff.push(
+                ChangeKind::VariantRemoved {
+                    enumeration: enumeration.clone(),
+                    variant: variant.get_name().clone(),
+                },
+                Breaking,
+                Breaking,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeKind, Compatibility};
+    use crate::sqlite_generator::generate_sqlite_migration;
+    use crate::{Attribute, AttributeType, BaseAttributeType, EnumVariant, Enumeration, ObjectDB};
+
+    fn string(name: &str) -> Attribute {
+        Attribute::new(name, AttributeType::Mandatory(BaseAttributeType::String))
+    }
+
+    #[test]
+    fn changes() {
+        let old = ObjectDB::builder()
+            .enumeration(
+                Enumeration::new("Genre")
+                    .variant(EnumVariant::new("Novel"))
+                    .variant(EnumVariant::new("Essay")),
+            )
+            .object("Author")
+            .table("authors")
+            .attr(string("name"))
+            .object("Writer")
+            .table("writers")
+            .attr(string("name"))
+            .object("Book")
+            .table("books")
+            .attr(string("title").mutable())
+            .attr(string("isbn").unique())
+            .attr(Attribute::new(
+                "summary",
+                AttributeType::Optional(BaseAttributeType::String),
+            ))
+            .attr(Attribute::new(
+                "author",
+                AttributeType::Mandatory(BaseAttributeType::Reference(String::from("Author"))),
+            ))
+            .build()
+            .unwrap();
+        let new = ObjectDB::builder()
+            .enumeration(
+                Enumeration::new("Genre")
+                    .variant(EnumVariant::new("Novel"))
+                    .variant(EnumVariant::new("Poetry")),
+            )
+            .object("Person")
+            .table("authors")
+            .attr(string("name"))
+            .object("Writer")
+            .table("writers")
+            .attr(string("name"))
+            .object("Book")
+            .table("books")
+            .attr(string("name").renamed_from("title"))
+            .attr(string("isbn"))
+            .attr(string("summary"))
+            .attr(Attribute::new(
+                "author",
+                AttributeType::Mandatory(BaseAttributeType::Reference(String::from("Writer"))),
+            ))
+            .attr(Attribute::new(
+                "pages",
+                AttributeType::Optional(BaseAttributeType::Integer),
+            ))
+            .attr(Attribute::new(
+                "genre",
+                AttributeType::Mandatory(BaseAttributeType::Enum(String::from("Genre"))),
+            ))
+            .build()
+            .unwrap();
+        assert!(old.diff(&old).is_empty());
+        let diff = old.diff(&new);
+        let changes: Vec<String> = diff.get_changes().map(|c| c.to_string()).collect();
+        assert_eq!(
+            vec![
+                "The object 'Author' is renamed 'Person' (database: compatible, code: breaking)",
+                "The attribute 'Book.title' is renamed 'name' (database: compatible, code: breaking)",
+                "The attribute 'Book.name' becomes immutable (database: compatible, code: breaking)",
+                "The attribute 'Book.isbn' is no longer unique (database: compatible, code: breaking)",
+                "The attribute 'Book.summary' becomes mandatory (database: breaking, code: breaking)",
+                "The attribute 'Book.author' references 'Writer' instead of 'Author' (database: breaking, code: breaking)",
+                "The attribute 'Book.pages' is added (database: compatible, code: breaking)",
+                "The attribute 'Book.genre' is added (database: breaking, code: breaking)",
+                "The variant 'Genre::Poetry' is added (database: compatible, code: breaking)",
+                "The variant 'Genre::Essay' is removed (database: breaking, code: breaking)",
+            ],
+            changes
+        );
+        assert!(diff.is_breaking_database());
+
+        //the reverse changes
+        let diff = new.diff(&old);
+        let first = diff.get_changes().next().unwrap();
+        assert_eq!(
+            &ChangeKind::ObjectRenamed {
+                from: String::from("Person"),
+                to: String::from("Author")
+            },
+            first.get_kind()
+        );
+        assert!(diff.get_changes().any(|c| c.get_kind()
+            == &ChangeKind::AttributeRemoved {
+                object: String::from("Book"),
+                attribute: String::from("pages")
+            }
+            && c.get_database_compatibility() == Compatibility::Breaking));
+    }
+
+    #[test]
+    fn table_taken_over() {
+        let old = ObjectDB::builder()
+            .object("Author")
+            .table("authors")
+            .attr(string("name"))
+            .build()
+            .unwrap();
+        //the new object using the table of the renamed one is listed first
+        let new = ObjectDB::builder()
+            .object("Contributor")
+            .table("authors")
+            .attr(string("name"))
+            .object("Writer")
+            .table("writers")
+            .renamed_from("Author")
+            .attr(string("name"))
+            .build()
+            .unwrap();
+        let changes: Vec<String> = old.diff(&new).get_changes().map(|c| c.to_string()).collect();
+        assert_eq!(
+            vec![
+                "The object 'Contributor' is added (database: compatible, code: compatible)",
+                "The object 'Author' is renamed 'Writer' (database: compatible, code: breaking)",
+                "The table of 'Writer' is renamed from 'authors' to 'writers' (database: compatible, code: compatible)",
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn name_taken_over() {
+        let old = ObjectDB::builder()
+            .object("Book")
+            .table("books")
+            .attr(string("b"))
+            .object("Shelf")
+            .table("shelves")
+            .build()
+            .unwrap();
+        //the renamed attribute and object are matched first, whatever their position
+        for renamed_first in [true, false] {
+            let renamed = string("a").renamed_from("b");
+            let added = Attribute::new("b", AttributeType::Optional(BaseAttributeType::String));
+            let (first, second) = if renamed_first { (renamed, added) } else { (added, renamed) };
+            let new = ObjectDB::builder()
+                .object("Book")
+                .table("books")
+                .attr(first)
+                .attr(second)
+                .object("Rack")
+                .table("racks")
+                .renamed_from("Shelf")
+                .object("Shelf")
+                .table("old_shelves")
+                .build()
+                .unwrap();
+            let diff = old.diff(&new);
+            let changes: Vec<&ChangeKind> = diff.get_changes().map(|c| c.get_kind()).collect();
+            assert!(changes.contains(&&ChangeKind::ObjectRenamed {
+                from: String::from("Shelf"),
+                to: String::from("Rack")
+            }));
+            assert!(changes.contains(&&ChangeKind::ObjectAdded {
+                object: String::from("Shelf")
+            }));
+            assert!(changes.contains(&&ChangeKind::AttributeRenamed {
+                object: String::from("Book"),
+                from: String::from("b"),
+                to: String::from("a")
+            }));
+            assert!(changes.contains(&&ChangeKind::AttributeAdded {
+                object: String::from("Book"),
+                attribute: String::from("b")
+            }));
+            assert_eq!(5, changes.len(), "{changes:?}");
+            //the previous column is only copied into the renamed one
+            let (_up, down) = generate_sqlite_migration(&old, &new).unwrap();
+            assert!(down.contains("INSERT INTO books_new (id, b, version)\n    SELECT id, a, version FROM books;"));
+        }
+    }
+}
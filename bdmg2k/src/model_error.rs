@@ -0,0 +1,450 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+///The kind of problem found in a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelErrorKind {
+    ///A file of the model can not be read
+    Io,
+    ///A file of the model is not written in the expected format, or does not
+    /// have the expected structure
+    Syntax,
+    ///An object, enumeration or mixin is used but not defined
+    UnknownReference,
+    ///A name is defined multiple times
+    DuplicateName,
+    ///A name can not be used as an identifier in the generated code or on database
+    InvalidIdentifier,
+    ///Any other invalid definition: a default value, a constraint, a unique group...
+    InvalidDefinition,
+    ///A file of the model is not written in its canonical form
+    NotCanonical,
+}
+
+///The place, in the files of the model, where an element is defined
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Location {
+    ///The file defining the element, if known
+    file: Option<String>,
+    ///The path to the element in the file, e.g. `objects[2].attr[0]`
+    path: String,
+}
+
+impl Location {
+    ///Create the location of an element of a file
+    pub(crate) fn new(file: Option<String>, path: String) -> Location {
+        Location { file, path }
+    }
+
+    ///Retrieve the location of a field of the element
+    pub(crate) fn child(&self, field: &str) -> Location {
+        let path = if self.path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{field}", self.path)
+        };
+        Location::new(self.file.clone(), path)
+    }
+
+    ///Retrieve the location of an element of an array field of the element
+    pub(crate) fn item(&self, field: &str, index: usize) -> Location {
+        let mut location = self.child(field);
+        location.path = format!("{}[{index}]", location.path);
+        location
+    }
+
+    ///Retrieve the file defining the element, if known
+    pub fn get_file(&self) -> Option<&String> {
+        self.file.as_ref()
+    }
+
+    ///Retrieve the path to the element in the file
+    pub fn get_path(&self) -> &String {
+        &self.path
+    }
+}
+
+///A problem found while loading or validating a model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelError {
+    kind: ModelErrorKind,
+    message: String,
+    location: Location,
+    ///The line and the column (starting at 1) of the element in the file, if known
+    position: Option<(usize, usize)>,
+}
+
+impl ModelError {
+    ///Create an error that is not related to a specific place of the model
+    pub fn new<S: Into<String>>(kind: ModelErrorKind, message: S) -> ModelError {
+        ModelError {
+            kind,
+            message: message.into(),
+            location: Location::default(),
+            position: None,
+        }
+    }
+
+    ///Set the place of the model where the problem lies
+    pub(crate) fn at(mut self, location: &Location) -> ModelError {
+        self.location = location.clone();
+        self
+    }
+
+    ///Set the file in which the problem lies
+    pub(crate) fn in_file(mut self, file: &str) -> ModelError {
+        self.location.file = Some(file.to_string());
+        self
+    }
+
+    ///Set the line and the column at which the problem lies
+    pub(crate) fn with_position(mut self, line: usize, column: usize) -> ModelError {
+        self.position = Some((line, column));
+        self
+    }
+
+    ///Retrieve the kind of problem
+    pub fn get_kind(&self) -> ModelErrorKind {
+        self.kind
+    }
+
+    ///Retrieve the description of the problem
+    pub fn get_message(&self) -> &String {
+        &self.message
+    }
+
+    ///Retrieve the place of the model where the problem lies
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    ///Retrieve the line and the column (starting at 1) at which the problem lies, if known
+    pub fn get_position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+}
+
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.location.get_file(), self.position) {
+            (Some(file), Some((line, column))) => write!(f, "{file}:{line}:{column}: ")?,
+            (Some(file), None) => write!(f, "{file}: ")?,
+            (None, Some((line, column))) => write!(f, "{line}:{column}: ")?,
+            (None, None) => {}
+        }
+        write!(f, "{}", self.message)?;
+        if !self.location.get_path().is_empty() {
+            write!(f, " (at {})", self.location.get_path())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+///The keywords of Rust, strict and reserved, which can not be used as identifiers
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+///The keywords of SQLite that can not be used as table or column names without
+/// being quoted, which the generated SQL does not do
+const SQL_KEYWORDS: &[&str] = &[
+    "add",
+    "all",
+    "alter",
+    "and",
+    "as",
+    "autoincrement",
+    "between",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "commit",
+    "constraint",
+    "create",
+    "current_date",
+    "current_time",
+    "current_timestamp",
+    "default",
+    "deferrable",
+    "delete",
+    "distinct",
+    "drop",
+    "else",
+    "escape",
+    "except",
+    "exists",
+    "foreign",
+    "from",
+    "group",
+    "having",
+    "if",
+    "in",
+    "index",
+    "insert",
+    "intersect",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "limit",
+    "not",
+    "nothing",
+    "notnull",
+    "null",
+    "on",
+    "or",
+    "order",
+    "primary",
+    "raise",
+    "references",
+    "returning",
+    "select",
+    "set",
+    "table",
+    "then",
+    "to",
+    "transaction",
+    "union",
+    "unique",
+    "update",
+    "using",
+    "values",
+    "when",
+    "where",
+];
+
+///Check that the name can be used as an identifier in the generated code
+pub(crate) fn check_identifier(
+    name: &str,
+    description: &str,
+    location: &Location,
+) -> Option<ModelError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let reason = if !valid {
+        "only ASCII letters, digits and underscores are allowed, and it can not start with a digit"
+    } else if RUST_KEYWORDS.contains(&name) {
+        "it is a keyword of Rust"
+    } else {
+        return None;
+    };
+    Some(
+        ModelError::new(
+            ModelErrorKind::InvalidIdentifier,
+            format!("The {description} '{name}' is not a valid identifier: {reason}"),
+        )
+        .at(location),
+    )
+}
+
+///Check that the name can be used as a table or column name, in the generated code
+/// and on database
+pub(crate) fn check_sql_identifier(
+    name: &str,
+    description: &str,
+    location: &Location,
+) -> Option<ModelError> {
+    if let Some(error) = check_identifier(name, description, location) {
+        return Some(error);
+    }
+    //the keywords of SQL are case insensitive
+    if SQL_KEYWORDS.contains(&name.to_ascii_lowercase().as_str()) {
+        return Some(
+            ModelError::new(
+                ModelErrorKind::InvalidIdentifier,
+                format!(
+                    "The {description} '{name}' is not a valid identifier: it is a keyword of SQL"
+                ),
+            )
+            .at(location),
+        );
+    }
+    None
+}
+
+///Retrieve the line and the column (starting at 1) of a byte offset in a content
+pub(crate) fn offset_position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+///Retrieve the line and the column (starting at 1) of the element designated by the
+/// path (e.g. `objects[2].attr[0]`) in a JSON content. The position of a field is the
+/// one of its key
+pub(crate) fn json_position(content: &str, path: &str) -> Option<(usize, usize)> {
+    let mut locator = JsonLocator {
+        content: content.as_bytes(),
+        index: 0,
+        target: path,
+    };
+    let offset = locator.value(String::new())?;
+    Some(offset_position(content, offset))
+}
+
+///A minimal JSON reader, looking for the offset of an element. The content is supposed
+/// to be valid, as it has already been read by serde
+struct JsonLocator<'a> {
+    content: &'a [u8],
+    index: usize,
+    target: &'a str,
+}
+
+impl JsonLocator<'_> {
+    fn skip_whitespaces(&mut self) {
+        while self.index < self.content.len() && self.content[self.index].is_ascii_whitespace() {
+            self.index += 1;
+        }
+    }
+
+    fn current(&self) -> Option<u8> {
+        self.content.get(self.index).copied()
+    }
+
+    ///Read a value, returning the offset of the target if it is found inside
+    fn value(&mut self, path: String) -> Option<usize> {
+        self.skip_whitespaces();
+        if path == self.target {
+            return Some(self.index);
+        }
+        match self.current()? {
+            b'{' => {
+                self.index += 1;
+                loop {
+                    self.skip_whitespaces();
+                    match self.current()? {
+                        b'}' => {
+                            self.index += 1;
+                            return None;
+                        }
+                        b',' => self.index += 1,
+                        _ => {
+                            let key_offset = self.index;
+                            let key = self.string()?;
+                            let child = if path.is_empty() {
+                                key
+                            } else {
+                                format!("{path}.{key}")
+                            };
+                            if child == self.target {
+                                return Some(key_offset);
+                            }
+                            self.skip_whitespaces();
+                            if self.current()? != b':' {
+                                return None;
+                            }
+                            self.index += 1;
+                            if let Some(offset) = self.value(child) {
+                                return Some(offset);
+                            }
+                        }
+                    }
+                }
+            }
+            b'[' => {
+                self.index += 1;
+                let mut item = 0;
+                loop {
+                    self.skip_whitespaces();
+                    match self.current()? {
+                        b']' => {
+                            self.index += 1;
+                            return None;
+                        }
+                        b',' => self.index += 1,
+                        _ => {
+                            if let Some(offset) = self.value(format!("{path}[{item}]")) {
+                                return Some(offset);
+                            }
+                            item += 1;
+                        }
+                    }
+                }
+            }
+            b'"' => {
+                self.string()?;
+                None
+            }
+            _ => {
+                while self.current().is_some_and(|c| !b",]} \t\r\n".contains(&c)) {
+                    self.index += 1;
+                }
+                None
+            }
+        }
+    }
+
+    ///Read a string, without decoding the escaped characters
+    fn string(&mut self) -> Option<String> {
+        self.index += 1;
+        let start = self.index;
+        loop {
+            match self.current()? {
+                b'\\' => self.index += 2,
+                b'"' => break,
+                _ => self.index += 1,
+            }
+        }
+        let value = String::from_utf8_lossy(&self.content[start..self.index]).into_owned();
+        self.index += 1;
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_position, Location, ModelError, ModelErrorKind};
+
+    #[test]
+    fn positions() {
+        let content = "{\n  \"objects\": [\n    {\"name\": \"A\", \"attr\": []},\n    {\"name\": \"B\",\n     \"attr\": [{\"name\": \"x\"}]}\n  ]\n}";
+        assert_eq!(Some((2, 3)), json_position(content, "objects"));
+        assert_eq!(Some((4, 5)), json_position(content, "objects[1]"));
+        assert_eq!(Some((5, 15)), json_position(content, "objects[1].attr[0]"));
+        assert_eq!(
+            Some((5, 16)),
+            json_position(content, "objects[1].attr[0].name")
+        );
+        assert_eq!(None, json_position(content, "objects[2]"));
+        let error = ModelError::new(
+            ModelErrorKind::DuplicateName,
+            "The object 'B' is defined twice",
+        )
+        .at(&Location::new(
+            Some(String::from("model.json")),
+            String::from("objects[1]"),
+        ))
+        .with_position(4, 5);
+        assert_eq!(
+            "model.json:4:5: The object 'B' is defined twice (at objects[1])",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,406 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::enumeration::Enumeration;
+use crate::mixin::Mixin;
+use crate::model_error::{offset_position, ModelError, ModelErrorKind};
+use crate::object::Object;
+use crate::objectdb::RustOutputType;
+
+use std::error::Error;
+use std::path::Path;
+
+///The formats in which a model can be written
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModelFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ModelFormat {
+    ///Retrieve the format corresponding to the extension of the file, if any:
+    /// `json`, `yaml` or `yml`, `toml`
+    pub fn from_path(path: &Path) -> Option<ModelFormat> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(ModelFormat::Json),
+            "yaml" | "yml" => Some(ModelFormat::Yaml),
+            "toml" => Some(ModelFormat::Toml),
+            _ => None,
+        }
+    }
+
+    ///Read a value written in this format. The syntax errors carry their position
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, ModelError> {
+        let syntax = |message: String| ModelError::new(ModelErrorKind::Syntax, message);
+        match self {
+            ModelFormat::Json => serde_json::from_str(content).map_err(|e| {
+                syntax(without_position(e.to_string())).with_position(e.line(), e.column())
+            }),
+            //the enumerations are written as in the other formats, `is: {Mandatory: String}`,
+            // rather than with the YAML tags expected by default
+            ModelFormat::Yaml => serde_yaml::with::singleton_map_recursive::deserialize(
+                serde_yaml::Deserializer::from_str(content),
+            )
+            .map_err(|e| match e.location() {
+                Some(location) => {
+                    syntax(without_position(e.to_string())).with_position(location.line(), location.column())
+                }
+                None => syntax(e.to_string()),
+            }),
+            ModelFormat::Toml => toml::from_str(content).map_err(|e| {
+                let error = syntax(e.message().to_string());
+                match e.span() {
+                    Some(span) => {
+                        let (line, column) = offset_position(content, span.start);
+                        error.with_position(line, column)
+                    }
+                    None => error,
+                }
+            }),
+        }
+    }
+
+    ///Write a value in this format
+    pub fn write<T: Serialize>(&self, value: &T) -> Result<String, String> {
+        match self {
+            ModelFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            ModelFormat::Yaml => {
+                let mut written = Vec::new();
+                serde_yaml::with::singleton_map_recursive::serialize(value, &mut serde_yaml::Serializer::new(&mut written))
+                    .map_err(|e| e.to_string())?;
+                String::from_utf8(written).map_err(|e| e.to_string())
+            }
+            ModelFormat::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+///Convert a model file into another format, both formats being given by the extension
+/// of the files. The content is converted as written: the included files are not
+/// converted and the order of the keys is kept, but the comments are lost
+pub fn convert_model_file(source: &Path, destination: &Path) -> Result<(), Box<dyn Error>> {
+    let format_of = |path: &Path| match ModelFormat::from_path(path) {
+        Some(format) => Ok(format),
+        None => Err(format!("Unknown model format for '{}'", path.display())),
+    };
+    let (source_format, destination_format) = (format_of(source)?, format_of(destination)?);
+    //the YAML values keep the order of the keys
+    let mut content: serde_yaml::Value = source_format.parse(&std::fs::read_to_string(source)?)?;
+    if destination_format == ModelFormat::Toml {
+        remove_nulls(&mut content);
+    }
+    std::fs::write(destination, destination_format.write(&content)?)?;
+    Ok(())
+}
+
+///The content of a model file, the root one or an included one, in its canonical layout
+#[derive(Serialize, Deserialize)]
+struct ModelFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rust_destination: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rust_output: Option<RustOutputType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    migrations: Option<String>,
+    #[serde(skip_serializing_if = "is_none_or_empty")]
+    include: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "is_none_or_empty")]
+    objects: Option<Vec<Object>>,
+    #[serde(skip_serializing_if = "is_none_or_empty")]
+    enums: Option<Vec<Enumeration>>,
+    #[serde(skip_serializing_if = "is_none_or_empty")]
+    mixins: Option<Vec<Mixin>>,
+}
+
+///Retrieve the canonical form of the content of a model file: the fields are written in the
+/// order of their definition, the fields having their default value are omitted and the
+/// aliases are replaced. The order of the elements (objects, attributes...) is kept, as it is
+/// the order of the generated code. The unknown fields are lost, and a content having comments
+/// is refused, as they could not be kept
+pub fn canonical_model(content: &str, format: ModelFormat) -> Result<String, ModelError> {
+    let file: ModelFile = format.parse(content)?;
+    if let Some((line, column)) = find_comment(content, format) {
+        return Err(ModelError::new(
+            ModelErrorKind::NotCanonical,
+            "The model has comments, which would be lost by writing it in its canonical form",
+        )
+        .with_position(line, column));
+    }
+    let written = format
+        .write(&file)
+        .map_err(|e| ModelError::new(ModelErrorKind::Syntax, e))?;
+    Ok(format!("{}\n", written.trim_end()))
+}
+
+///Write a model file in its canonical form, the format being given by the extension of the
+/// file (JSON by default). The included files are not formatted, and a file having comments is
+/// left untouched with an error. Return whether the file changed
+pub fn format_model_file(path: &Path) -> Result<bool, ModelError> {
+    let (content, canonical) = read_canonical(path)?;
+    if content == canonical {
+        return Ok(false);
+    }
+    let file_name = path.display().to_string();
+    std::fs::write(path, canonical).map_err(|e| {
+        ModelError::new(ModelErrorKind::Io, format!("Unable to write '{file_name}': {e}")).in_file(&file_name)
+    })?;
+    Ok(true)
+}
+
+///Check that a model file is written in its canonical form. The error gives the first line
+/// that differs from the canonical form, or the first comment of the file
+pub fn check_model_file(path: &Path) -> Result<(), ModelError> {
+    let (content, canonical) = read_canonical(path)?;
+    let mut lines = content.lines().zip(canonical.lines());
+    let line = match lines.position(|(line, canonical_line)| line != canonical_line) {
+        Some(index) => index + 1,
+        None if content == canonical => return Ok(()),
+        None => content.lines().count().min(canonical.lines().count()) + 1,
+    };
+    Err(ModelError::new(
+        ModelErrorKind::NotCanonical,
+        "The model is not written in its canonical form",
+    )
+    .in_file(&path.display().to_string())
+    .with_position(line, 1))
+}
+
+///Read a model file, returning its content and its canonical form
+fn read_canonical(path: &Path) -> Result<(String, String), ModelError> {
+    let file_name = path.display().to_string();
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ModelError::new(ModelErrorKind::Io, format!("Unable to open '{file_name}': {e}")).in_file(&file_name)
+    })?;
+    let format = ModelFormat::from_path(path).unwrap_or(ModelFormat::Json);
+    let canonical = canonical_model(&content, format).map_err(|e| e.in_file(&file_name))?;
+    Ok((content, canonical))
+}
+
+///Check if an optional value is absent or has its default value, in which case it is not written
+pub(crate) fn is_default<T: Default + PartialEq>(value: &Option<T>) -> bool {
+    match value {
+        Some(v) => *v == T::default(),
+        None => true,
+    }
+}
+
+///Check if an optional list is absent or empty, in which case it is not written
+pub(crate) fn is_none_or_empty<T>(value: &Option<Vec<T>>) -> bool {
+    match value {
+        Some(values) => values.is_empty(),
+        None => true,
+    }
+}
+
+///Find the first comment of a content, returning its line and column. The comments are only
+/// searched outside of the strings, the YAML block scalars being strings as well
+fn find_comment(content: &str, format: ModelFormat) -> Option<(usize, usize)> {
+    match format {
+        ModelFormat::Json => None,
+        ModelFormat::Yaml => find_yaml_comment(content),
+        ModelFormat::Toml => find_toml_comment(content),
+    }
+}
+
+///Find the first comment of a YAML content: a `#` starting a line or following a space
+fn find_yaml_comment(content: &str) -> Option<(usize, usize)> {
+    let indentation = |line: &str| line.len() - line.trim_start().len();
+    //the quote of the string being read, which may span multiple lines
+    let mut quote: Option<char> = None;
+    //the indentation of the line introducing the block scalar being read
+    let mut block: Option<usize> = None;
+    for (index, line) in content.lines().enumerate() {
+        if let Some(block_indentation) = block {
+            if line.trim().is_empty() || indentation(line) > block_indentation {
+                continue;
+            }
+            block = None;
+        }
+        let mut previous: Option<char> = None;
+        let mut chars = line.chars().enumerate().peekable();
+        while let Some((column, c)) = chars.next() {
+            match quote {
+                Some('"') if c == '\\' => {
+                    chars.next();
+                }
+                Some('\'') if c == '\'' && chars.peek().map(|(_column, next)| *next) == Some('\'') => {
+                    chars.next();
+                }
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '#' && previous.is_none_or(char::is_whitespace) => return Some((index + 1, column + 1)),
+                None if (c == '"' || c == '\'') && previous.is_none_or(|p| p.is_whitespace() || "[{,".contains(p)) => {
+                    quote = Some(c)
+                }
+                None => {}
+            }
+            previous = Some(c);
+        }
+        //a block scalar is introduced by `|` or `>`, followed by its optional indicators
+        let header = line.trim_end().trim_end_matches(|c: char| c == '-' || c == '+' || c.is_ascii_digit());
+        if quote.is_none() && (header.ends_with(" |") || header.ends_with(" >") || header == "|" || header == ">") {
+            block = Some(indentation(line));
+        }
+    }
+    None
+}
+
+///Find the first comment of a TOML content: a `#` outside of the strings
+fn find_toml_comment(content: &str) -> Option<(usize, usize)> {
+    //the delimiter of the string being read: a quote, or three of them for a multi-line string
+    let mut delimiter: Option<&str> = None;
+    let (mut line, mut column) = (1, 0);
+    let mut index = 0;
+    while let Some(c) = content[index..].chars().next() {
+        let rest = &content[index..];
+        let mut length = c.len_utf8();
+        column += 1;
+        match delimiter {
+            Some(d) if d.starts_with('"') && c == '\\' => {
+                length += rest[1..].chars().next().map_or(0, char::len_utf8);
+            }
+            Some(d) if rest.starts_with(d) => {
+                length = d.len();
+                delimiter = None;
+            }
+            Some(_) => {}
+            None if c == '#' => return Some((line, column)),
+            None => {
+                delimiter = ["\"\"\"", "'''", "\"", "'"].into_iter().find(|d| rest.starts_with(d));
+                if let Some(d) = delimiter {
+                    length = d.len();
+                }
+            }
+        }
+        if content[index..index + length].contains('\n') {
+            line += 1;
+            column = 0;
+        }
+        index += length;
+    }
+    None
+}
+
+///Remove the position appended to the message of a parsing error, as it is kept apart
+fn without_position(message: String) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message,
+    }
+}
+
+///Remove the keys without value, as TOML can not represent them. An absent key
+/// has the same meaning in the model
+fn remove_nulls(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            mapping.retain(|_key, v| !v.is_null());
+            for (_key, v) in mapping.iter_mut() {
+                remove_nulls(v);
+            }
+        }
+        serde_yaml::Value::Sequence(values) => {
+            for v in values.iter_mut() {
+                remove_nulls(v);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_model, check_model_file, format_model_file, ModelFormat};
+    use crate::ModelErrorKind;
+
+    #[test]
+    fn canonical() {
+        let content = "{\"objects\": [{\"attr\": [
+            {\"mutable\": false, \"is\": {\"Mandatory\": \"String\"}, \"name\": \"code\", \"indexable\": true, \"comm\": null},
+            {\"name\": \"size\", \"is\": {\"Optional\": \"Integer\"}, \"min\": \"0\", \"case_insensitive\": false}
+        ], \"name\": \"Item\", \"tbnm\": \"items\", \"id\": \"Integer\", \"unique\": []}], \"rust_destination\": \"out\", \"enums\": null}";
+        let canonical = canonical_model(content, ModelFormat::Json).unwrap();
+        assert_eq!(
+            "{
+  \"rust_destination\": \"out\",
+  \"objects\": [
+    {
+      \"tbnm\": \"items\",
+      \"name\": \"Item\",
+      \"attr\": [
+        {
+          \"name\": \"code\",
+          \"is\": {
+            \"Mandatory\": \"String\"
+          },
+          \"unique\": true
+        },
+        {
+          \"name\": \"size\",
+          \"is\": {
+            \"Optional\": \"Integer\"
+          },
+          \"min\": \"0\"
+        }
+      ]
+    }
+  ]
+}
+",
+            canonical
+        );
+        assert_eq!(canonical, canonical_model(&canonical, ModelFormat::Json).unwrap());
+        let toml = ModelFormat::Toml.write(&ModelFormat::Json.parse::<toml::Value>(&canonical).unwrap()).unwrap();
+        let canonical_toml = canonical_model(&toml, ModelFormat::Toml).unwrap();
+        assert_eq!(canonical_toml, canonical_model(&canonical_toml, ModelFormat::Toml).unwrap());
+
+        //the check fails on the first line differing from the canonical form
+        let path = std::env::temp_dir().join(format!("bdmg2k_canonical_{}.json", std::process::id()));
+        std::fs::write(&path, canonical.replace("\"unique\": true", "\"indexable\": true")).unwrap();
+        let error = check_model_file(&path).unwrap_err();
+        assert_eq!(ModelErrorKind::NotCanonical, error.get_kind());
+        assert_eq!(Some((13, 1)), error.get_position());
+        assert!(format_model_file(&path).unwrap());
+        assert!(!format_model_file(&path).unwrap());
+        assert!(check_model_file(&path).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn comments() {
+        //the comments are refused, as they would be lost
+        let yaml = "objects:\n  - tbnm: items # the items\n    name: Item\n    attr: []\n";
+        let error = canonical_model(yaml, ModelFormat::Yaml).unwrap_err();
+        assert_eq!(ModelErrorKind::NotCanonical, error.get_kind());
+        assert_eq!(Some((2, 17)), error.get_position());
+        let toml = "# the items\n[[objects]]\ntbnm = \"items\"\nname = \"Item\"\nattr = []\n";
+        assert_eq!(Some((1, 1)), canonical_model(toml, ModelFormat::Toml).unwrap_err().get_position());
+
+        //but not a `#` written in a string
+        let yaml = "objects:\n  - tbnm: items\n    name: Item\n    comm: |\n      # the items\n      of the shop\n    attr:\n      - name: 'code'\n        is: {Mandatory: String}\n        comm: \"the #code, 'quoted'\"\n";
+        let canonical = canonical_model(yaml, ModelFormat::Yaml).unwrap();
+        assert!(canonical.contains("# the items"));
+        let toml = "[[objects]]\ntbnm = \"items\"\nname = \"Item\"\ncomm = \"\"\"\n# the items\n\"\"\"\nattr = [{name = 'code#', is = {Mandatory = \"String\"}, comm = \"the \\\"#code\\\"\"}]\n";
+        assert!(canonical_model(toml, ModelFormat::Toml).is_ok());
+    }
+}
@@ -17,9 +17,12 @@
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
 
-use crate::attributes::Attribute;
+use crate::attributes::{Attribute, BaseAttributeType, IdentifierType};
+use crate::enumeration::Enumeration;
+use crate::mixin::Mixin;
+use crate::model_error::{check_identifier, check_sql_identifier, Location, ModelError, ModelErrorKind};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
 pub struct Object {
@@ -27,12 +30,17 @@ pub struct Object {
     tbnm: String,
     /// The name of the object type
     name: String,
+    /// Optional, the type of the primary key. Default is an autoincremented integer
+    #[serde(skip_serializing_if = "crate::model_format::is_default")]
+    id: Option<IdentifierType>,
     /// The array of attribute of this object
     attr: Vec<Attribute>,
     /// The comments related to this object
+    #[serde(skip_serializing_if = "Option::is_none")]
     comm: Option<String>,
     /// The category in which the object should be set. This category name
     /// does not prevent name clashes if two objects have the same name
+    #[serde(skip_serializing_if = "Option::is_none")]
     category: Option<String>,
     /// A reference to a function that will check the validity of the instance.
     /// This validity check will be performed whenever an object is created
@@ -41,13 +49,99 @@ pub struct Object {
     /// database. Therefore, a loaded instance may not pass this check
     /// if something went wrong in the database or if a user made change
     /// to the database directly
+    #[serde(skip_serializing_if = "Option::is_none")]
     validator: Option<String>,
+    /// Optional, the groups of attributes whose combination of values must be
+    /// unique (e.g. `[["tenant", "code"]]`). A lookup function is generated
+    /// for each group. As SQLite considers the NULL values as distinct, a partial
+    /// unique index also covers the combinations where optional attributes have no value
+    #[serde(skip_serializing_if = "crate::model_format::is_none_or_empty")]
+    unique: Option<Vec<Vec<String>>>,
+    /// Optional, the previous name of the object, so that renaming it is not seen as
+    /// removing an object and adding another one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    renamed_from: Option<String>,
+    /// Optional, the names of the mixins whose attributes are shared by this object.
+    /// The attributes of the mixins are placed before the attributes of the object
+    #[serde(skip_serializing_if = "crate::model_format::is_none_or_empty")]
+    mixins: Option<Vec<String>>,
+    /// The attributes of the included mixins, placed before the attributes of the object.
+    /// They are kept apart, so that the object is written as it is defined
+    #[serde(skip)]
+    included_attr: Vec<Attribute>,
     /// The list of objects that are referencing this object
     #[serde(skip)]
     referencing: Vec<String>,
+    /// The place where the object is defined in the model
+    #[serde(skip)]
+    location: Location,
 }
 
 impl Object {
+    ///Create an object without attributes, stored in the given table
+    pub(crate) fn new(name: &str, table_name: &str) -> Object {
+        Object {
+            tbnm: table_name.to_string(),
+            name: name.to_string(),
+            id: None,
+            attr: Vec::new(),
+            comm: None,
+            category: None,
+            validator: None,
+            unique: None,
+            renamed_from: None,
+            mixins: None,
+            included_attr: Vec::new(),
+            referencing: Vec::new(),
+            location: Location::default(),
+        }
+    }
+
+    ///Set the name of the database table that contain the object
+    pub(crate) fn set_table_name(&mut self, table_name: &str) {
+        self.tbnm = table_name.to_string();
+    }
+
+    ///Set the type of the primary key of the object
+    pub(crate) fn set_id_type(&mut self, id_type: IdentifierType) {
+        self.id = Some(id_type);
+    }
+
+    ///Set the description of the object
+    pub(crate) fn set_description(&mut self, description: &str) {
+        self.comm = Some(description.to_string());
+    }
+
+    ///Set the category in which this object is defined
+    pub(crate) fn set_category(&mut self, category: &str) {
+        self.category = Some(category.to_string());
+    }
+
+    ///Set the function checking the validity of the instances
+    pub(crate) fn set_validator(&mut self, validator: &str) {
+        self.validator = Some(validator.to_string());
+    }
+
+    ///Set the previous name of the object
+    pub(crate) fn set_renamed_from(&mut self, previous_name: &str) {
+        self.renamed_from = Some(previous_name.to_string());
+    }
+
+    ///Add an attribute after the attributes of the object
+    pub(crate) fn add_attribute(&mut self, attribute: Attribute) {
+        self.attr.push(attribute);
+    }
+
+    ///Add a group of attributes whose combination of values must be unique
+    pub(crate) fn add_unique_group(&mut self, group: Vec<String>) {
+        self.unique.get_or_insert_with(Vec::new).push(group);
+    }
+
+    ///Add a mixin included by the object
+    pub(crate) fn add_mixin(&mut self, mixin_name: &str) {
+        self.mixins.get_or_insert_with(Vec::new).push(mixin_name.to_string());
+    }
+
     ///Retrieve the name of the object as defined in the object store
     pub fn get_name(&self) -> &String {
         &self.name
@@ -58,6 +152,16 @@ impl Object {
         &self.tbnm
     }
 
+    ///Retrieve the previous name of the object, if it was renamed
+    pub fn get_renamed_from(&self) -> Option<&String> {
+        self.renamed_from.as_ref()
+    }
+
+    ///Retrieve the type of the primary key of the object
+    pub fn get_id_type(&self) -> IdentifierType {
+        self.id.unwrap_or_default()
+    }
+
     //Check if there is a validator installed on this object
     pub fn get_validator(&self) -> &Option<String> {
         &self.validator
@@ -73,20 +177,71 @@ impl Object {
         &self.comm
     }
 
-    ///Retrieve an iterator to the attributes of this object
-    pub fn get_attributes(&self) -> std::slice::Iter<'_, Attribute> {
-        self.attr.iter()
+    ///Retrieve an iterator to the attributes of this object, starting with the
+    /// attributes of the included mixins
+    pub fn get_attributes(&self) -> std::iter::Chain<std::slice::Iter<'_, Attribute>, std::slice::Iter<'_, Attribute>> {
+        self.included_attr.iter().chain(self.attr.iter())
+    }
+
+    ///Retrieve the groups of attributes whose combination of values is unique
+    pub fn get_unique_groups(&self) -> std::slice::Iter<'_, Vec<String>> {
+        match &self.unique {
+            Some(groups) => groups.iter(),
+            None => [].iter(),
+        }
+    }
+
+    ///Retrieve the names of the mixins included by this object
+    pub fn get_mixins(&self) -> std::slice::Iter<'_, String> {
+        match &self.mixins {
+            Some(mixins) => mixins.iter(),
+            None => [].iter(),
+        }
+    }
+
+    ///Add the attributes of the included mixins to the attributes of the object.
+    ///Parameter: all the mixins defined in the object store.
+    ///The unknown mixins and the attributes defined twice are reported by the validation
+    pub(crate) fn include_mixins(&mut self, mixins: &[Mixin]) {
+        let mut attributes = Vec::new();
+        for mixin_name in self.get_mixins() {
+            if let Some(mixin) = mixins.iter().find(|m| m.get_name() == mixin_name) {
+                attributes.extend(mixin.get_attributes().cloned());
+            }
+        }
+        self.included_attr = attributes;
+    }
+
+    ///Retrieve the place where the object is defined in the model
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    ///Set the place where the object and its attributes are defined in the model
+    pub(crate) fn set_location(&mut self, location: Location) {
+        for (index, at) in self.attr.iter_mut().enumerate() {
+            at.set_location(location.item("attr", index));
+        }
+        self.location = location;
+    }
+
+    ///Retrieve the attributes of a group, skipping the unknown names
+    pub fn get_group_attributes(&self, group: &[String]) -> Vec<&Attribute> {
+        group
+            .iter()
+            .filter_map(|name| self.get_attributes().find(|at| at.get_name() == name))
+            .collect()
     }
 
     ///Check if the object has attributes, private or not
     pub fn has_attributes(&self) -> bool {
-        !self.attr.is_empty()
+        self.get_attributes().next().is_some()
     }
 
     ///Check if the object has public attribues. A public attribute
     /// is an attribute that is not secret.
     pub fn has_public_attributes(&self) -> bool {
-        for at in &self.attr {
+        for at in self.get_attributes() {
             if !at.is_secret() {
                 return true;
             }
@@ -110,6 +265,46 @@ impl Object {
         None
     }
 
+    /// Retrieve all the attributes referencing the object whose name is the
+    /// given parameter. An object may reference the same type multiple times,
+    /// each attribute giving a different role to the referenced instance
+    pub fn get_relation_attributes(&self, referenced: &str) -> Vec<&Attribute> {
+        self.get_attributes()
+            .filter(|at| at.get_reference().is_some_and(|r| r == referenced))
+            .collect()
+    }
+
+    /// For an object representing a n-to-n relation, retrieve the pairs of attributes
+    /// (referencing the given object, referencing the other side of the relation).
+    /// A relation between instances of the same type has two pairs
+    pub fn get_relation_sides(&self, referenced: &str) -> Vec<(&Attribute, &Attribute)> {
+        let references: Vec<&Attribute> = self
+            .get_attributes()
+            .filter(|at| at.get_reference().is_some())
+            .collect();
+        if self.is_object_relation().is_none() || references.len() != 2 {
+            return vec![];
+        }
+        let mut sides = Vec::new();
+        if references[0].get_reference().is_some_and(|r| r == referenced) {
+            sides.push((references[0], references[1]));
+        }
+        if references[1].get_reference().is_some_and(|r| r == referenced) {
+            sides.push((references[1], references[0]));
+        }
+        sides
+    }
+
+    /// Retrieve the attribute referencing the parent of an instance, when the object
+    /// forms a hierarchy: the object has exactly one attribute referencing the object
+    /// itself, and this attribute is optional so that the roots have no parent
+    pub fn get_hierarchy_attribute(&self) -> Option<&Attribute> {
+        match self.get_relation_attributes(self.get_name())[..] {
+            [parent] if parent.is_optional() => Some(parent),
+            _ => None,
+        }
+    }
+
     ///Check if the object has any relation defined in this object.
     ///Note: only the object containing a reference to some object
     ///will return true. Not the object being referenced.
@@ -123,6 +318,20 @@ impl Object {
         false
     }
 
+    ///Set the type of the identifier of the objects referenced by the attributes,
+    /// based on the types of primary key of all objects
+    pub(crate) fn set_reference_id_types(&mut self, id_types: &BTreeMap<String, IdentifierType>) {
+        for at in self.included_attr.iter_mut().chain(self.attr.iter_mut()) {
+            let id_type = match at.get_reference() {
+                Some(r) => id_types.get(r).copied(),
+                None => None,
+            };
+            if let Some(id_type) = id_type {
+                at.set_reference_id_type(id_type);
+            }
+        }
+    }
+
     ///Add the knowledge that this object is being referenced in some
     /// object having the given name
     pub fn add_referencing_object(&mut self, referencing: String) {
@@ -201,37 +410,292 @@ impl Object {
         }
     }
 
-    ///Check that every refenced object has a definition
-    ///Parmeter: the map containg all object indexed by their name
+    ///Check that every refenced object, enumeration and mixin has a definition, and that
+    ///the definitions of the object and of its attributes are valid. All the problems are returned.
+    ///Parmeters: the maps containg all objects, enumerations and mixins indexed by their name
     pub fn validate<'a, 'b, 'c>(
         &self,
         objects_map: &'c HashMap<&'a String, &'b Object>,
-    ) -> Result<(), String> {
-        let mut refered_objects = HashMap::with_capacity(objects_map.len());
+        enums_map: &'c HashMap<&'a String, &'b Enumeration>,
+        mixins_map: &'c HashMap<&'a String, &'b Mixin>,
+    ) -> Vec<ModelError> {
+        let mut errors = Vec::new();
+        let object_name = self.get_name();
+        errors.extend(check_identifier(object_name, "object name", &self.location.child("name")));
+        errors.extend(check_sql_identifier(self.get_table_name(), "table name", &self.location.child("tbnm")));
+        for (index, mixin_name) in self.get_mixins().enumerate() {
+            if !mixins_map.contains_key(mixin_name) {
+                errors.push(
+                    ModelError::new(
+                        ModelErrorKind::UnknownReference,
+                        format!("Unknown mixin '{mixin_name}' in '{object_name}'"),
+                    )
+                    .at(&self.location.item("mixins", index)),
+                );
+            }
+        }
+        for (index, group) in self.get_unique_groups().enumerate() {
+            let attributes = self.get_group_attributes(group);
+            let mut names: Vec<&String> = group.iter().collect();
+            names.sort();
+            names.dedup();
+            if group.len() < 2 || attributes.len() != group.len() || names.len() != group.len() {
+                errors.push(
+                    ModelError::new(
+                        ModelErrorKind::InvalidDefinition,
+                        format!(
+                            "Invalid unique group ({group}) in '{object_name}': a group must contain at least two different attributes of the object",
+                            group = group.join(", ")
+                        ),
+                    )
+                    .at(&self.location.item("unique", index)),
+                );
+            }
+        }
+        let mut attribute_names = std::collections::HashSet::new();
         for at in self.get_attributes() {
-            match at.get_reference() {
-                Some(r) => {
-                    if !objects_map.contains_key(r) {
-                        return Err(format!(
-                            "Unknown referenced type '{ref_type_name}' in '{object_name}.{attribute_name}'",
-                            ref_type_name = r,
-                            object_name = self.get_name(),
-                            attribute_name = at.get_name()
-                        ));
-                    }
-                    //There is currently a problem if an object has two references to the same object.
-                    //The problem lies with the current implementation of back references: the referenced
-                    //object will have 2 or more functions with the same name.
-                    match refered_objects.get(r) {
-                        Some(v) => return Err(format!("Double reference to the same object in '{object_name}': both '{first}' and '{second}' are referencing the type '{ref_type_name}'.", object_name = self.get_name(), first = v, second = at.get_name(), ref_type_name = r)),
-                        None => {
-                            refered_objects.insert(r, at.get_name());
-                        }
-                    };
+            let attribute_name = at.get_name();
+            let location = at.get_location();
+            //the problem is reported where the attribute is defined, in the object or in a mixin
+            let error = |kind: ModelErrorKind, field: &str, message: String| {
+                let at_location = if field.is_empty() { location.clone() } else { location.child(field) };
+                ModelError::new(kind, message).at(&at_location)
+            };
+            errors.extend(check_sql_identifier(attribute_name, "attribute name", &location.child("name")));
+            //the columns of the identifier and of the version are added to every table
+            if attribute_name.eq_ignore_ascii_case("id") || attribute_name.eq_ignore_ascii_case("version") {
+                errors.push(error(
+                    ModelErrorKind::InvalidIdentifier,
+                    "name",
+                    format!("The attribute name '{attribute_name}' of '{object_name}' is used by a generated column"),
+                ));
+            }
+            if !attribute_names.insert(attribute_name) {
+                errors.push(error(
+                    ModelErrorKind::DuplicateName,
+                    "",
+                    format!("The attribute '{attribute_name}' is defined twice in '{object_name}'"),
+                ));
+            }
+            if let Some(e) = at.get_enum() {
+                if !enums_map.contains_key(e) {
+                    errors.push(error(
+                        ModelErrorKind::UnknownReference,
+                        "is",
+                        format!("Unknown enumeration '{e}' in '{object_name}.{attribute_name}'"),
+                    ));
                 }
-                None => {}
             }
+            if at.get_max_size().is_some()
+                && at.get_type().get_base_type() != &crate::BaseAttributeType::Bytes
+            {
+                errors.push(error(
+                    ModelErrorKind::InvalidDefinition,
+                    "max_size",
+                    format!("A maximum size is defined on '{object_name}.{attribute_name}' which is not a binary attribute"),
+                ));
+            }
+            if let crate::BaseAttributeType::Decimal { precision, scale } =
+                at.get_type().get_base_type()
+            {
+                //28 is the maximum number of significant digits of the rust decimals
+                if *precision == 0 || *precision > 28 || scale > precision {
+                    errors.push(error(
+                        ModelErrorKind::InvalidDefinition,
+                        "is",
+                        format!("Invalid decimal({precision}, {scale}) in '{object_name}.{attribute_name}': the precision must be between 1 and 28 and the scale can not exceed the precision"),
+                    ));
+                }
+            }
+            if let Err(reason) = at.get_constraints().validate(at.get_type().get_base_type()) {
+                errors.push(error(
+                    ModelErrorKind::InvalidDefinition,
+                    "",
+                    format!("Invalid constraints on '{object_name}.{attribute_name}': {reason}"),
+                ));
+            }
+            if let Some(default) = at.get_default() {
+                if let Err(reason) = check_default(at, default, enums_map) {
+                    errors.push(error(
+                        ModelErrorKind::InvalidDefinition,
+                        "default",
+                        format!("Invalid default value '{default}' for '{object_name}.{attribute_name}': {reason}"),
+                    ));
+                }
+            }
+            if let Some(r) = at.get_reference() {
+                if !objects_map.contains_key(r) {
+                    errors.push(error(
+                        ModelErrorKind::UnknownReference,
+                        "is",
+                        format!("Unknown referenced type '{r}' in '{object_name}.{attribute_name}'"),
+                    ));
+                }
+                //the first instance could never be created
+                if r == object_name && !at.is_optional() {
+                    errors.push(error(
+                        ModelErrorKind::InvalidDefinition,
+                        "is",
+                        format!("Mandatory reference to the object itself in '{object_name}.{attribute_name}': a self reference must be optional"),
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Check that the default value of an attribute can be used, both by the
+/// generated rust code and in the SQL table definition
+fn check_default(
+    attribute: &Attribute,
+    default: &str,
+    enums_map: &HashMap<&String, &Enumeration>,
+) -> Result<(), String> {
+    if attribute.is_optional() {
+        return Err(String::from("only mandatory attributes may have a default value"));
+    }
+    let valid = match attribute.get_type().get_base_type() {
+        BaseAttributeType::Integer => default.parse::<i64>().is_ok(),
+        BaseAttributeType::Float => default.parse::<f64>().is_ok_and(f64::is_finite),
+        BaseAttributeType::Decimal { precision, scale } => {
+            let digits = default.strip_prefix('-').unwrap_or(default);
+            let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+            let fraction = fraction.trim_end_matches('0');
+            //an invalid precision is reported on its own, the default can not fit in it
+            let integer_digits = precision.checked_sub(*scale);
+            !integer.is_empty()
+                && (integer.chars().chain(fraction.chars())).all(|c| c.is_ascii_digit())
+                && integer_digits.is_some_and(|digits| integer.trim_start_matches('0').len() as u32 <= digits)
+                && fraction.len() as u32 <= *scale
+        }
+        BaseAttributeType::Boolean => default == "true" || default == "false",
+        //the values are parsed as done by the generated code
+        BaseAttributeType::Date => default.parse::<chrono::NaiveDate>().is_ok(),
+        BaseAttributeType::Time => default.parse::<chrono::NaiveTime>().is_ok(),
+        BaseAttributeType::Timestamp => default.parse::<chrono::DateTime<chrono::Utc>>().is_ok(),
+        BaseAttributeType::Uuid => uuid::Uuid::parse_str(default).is_ok(),
+        BaseAttributeType::Json | BaseAttributeType::TypedJson(_) => {
+            serde_json::from_str::<serde_json::Value>(default).is_ok()
+        }
+        BaseAttributeType::Enum(e) => match enums_map.get(e) {
+            Some(enumeration) => enumeration.get_variants().any(|v| v.get_name() == default),
+            None => true,
+        },
+        BaseAttributeType::String => true,
+        //the stored representation differs from the string representation
+        BaseAttributeType::Duration | BaseAttributeType::Bytes => {
+            return Err(String::from("default values are not supported for this type"))
+        }
+        BaseAttributeType::Reference(_) => {
+            return Err(String::from("a reference can not have a default value"))
         }
+    };
+    if valid {
         Ok(())
+    } else {
+        Err(String::from("the value can not be parsed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_default, Object};
+    use crate::mixin::Mixin;
+    use crate::{Attribute, BaseAttributeType};
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn multiple_references() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"friendships\", \"name\": \"Friendship\", \"attr\": [
+                {\"name\": \"left\", \"is\": {\"Mandatory\": {\"Reference\": \"User\"}}},
+                {\"name\": \"right\", \"is\": {\"Mandatory\": {\"Reference\": \"User\"}}}
+            ]}",
+        )
+        .unwrap();
+        let names: Vec<&String> = object
+            .get_relation_attributes("User")
+            .iter()
+            .map(|at| at.get_name())
+            .collect();
+        assert_eq!(vec!["left", "right"], names);
+        let sides: Vec<(&String, &String)> = object
+            .get_relation_sides("User")
+            .iter()
+            .map(|(at, other)| (at.get_name(), other.get_name()))
+            .collect();
+        assert_eq!(
+            vec![(&String::from("left"), &String::from("right")), (&String::from("right"), &String::from("left"))],
+            sides
+        );
+        assert!(object.get_relation_sides("Group").is_empty());
+    }
+
+    #[test]
+    fn mixins() {
+        let mixins: Vec<Mixin> = serde_json::from_slice(
+            b"[{\"name\": \"Named\", \"attr\": [{\"name\": \"name\", \"is\": {\"Mandatory\": \"String\"}}]}]",
+        )
+        .unwrap();
+        let mut object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"tags\", \"name\": \"Tag\", \"mixins\": [\"Named\"], \"attr\": [
+                {\"name\": \"weight\", \"is\": {\"Mandatory\": \"Integer\"}}
+            ]}",
+        )
+        .unwrap();
+        object.include_mixins(&mixins);
+        let names: Vec<&String> = object.get_attributes().map(|at| at.get_name()).collect();
+        assert_eq!(vec!["name", "weight"], names);
+    }
+
+    #[test]
+    fn hierarchy() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"categories\", \"name\": \"Category\", \"attr\": [
+                {\"name\": \"label\", \"is\": {\"Mandatory\": \"String\"}},
+                {\"name\": \"parent\", \"is\": {\"Optional\": {\"Reference\": \"Category\"}}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!("parent", object.get_hierarchy_attribute().unwrap().get_name());
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"people\", \"name\": \"Person\", \"attr\": [
+                {\"name\": \"mother\", \"is\": {\"Optional\": {\"Reference\": \"Person\"}}},
+                {\"name\": \"father\", \"is\": {\"Optional\": {\"Reference\": \"Person\"}}}
+            ]}",
+        )
+        .unwrap();
+        assert!(object.get_hierarchy_attribute().is_none());
+    }
+
+    #[test]
+    fn default_values() {
+        let check = |base: BaseAttributeType, default: &str| {
+            let attribute: Attribute = serde_json::from_value(serde_json::json!({
+                "name": "value",
+                "is": {"Mandatory": base},
+                "default": default
+            }))
+            .unwrap();
+            check_default(&attribute, default, &HashMap::new())
+        };
+        assert!(check(BaseAttributeType::Date, "2020-01-02").is_ok());
+        assert!(check(BaseAttributeType::Date, "not-a-date").is_err());
+        assert!(check(BaseAttributeType::Time, "12:30:00").is_ok());
+        assert!(check(BaseAttributeType::Time, "25:00:00").is_err());
+        assert!(check(BaseAttributeType::Timestamp, "2020-01-02T03:04:05Z").is_ok());
+        assert!(check(BaseAttributeType::Timestamp, "2020-01-02").is_err());
+        assert!(check(BaseAttributeType::Uuid, "67e55044-10b1-426f-9247-bb680e5fe0c8").is_ok());
+        assert!(check(BaseAttributeType::Uuid, "67e55044-10b1-426f-9247-bb680e5fe0cz").is_err());
+        assert!(check(BaseAttributeType::Json, "{\"a\": 1}").is_ok());
+        assert!(check(BaseAttributeType::Json, "{oops").is_err());
+        assert!(check(BaseAttributeType::TypedJson(String::from("Settings")), "[1, 2").is_err());
+
+        //the scale can not exceed the precision
+        let decimal = |precision, scale| BaseAttributeType::Decimal { precision, scale };
+        assert!(check(decimal(4, 2), "12.5").is_ok());
+        assert!(check(decimal(2, 4), "0.1").is_err());
     }
 }
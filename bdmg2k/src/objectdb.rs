@@ -17,13 +17,17 @@
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
 
+use crate::builder::ModelBuilder;
+use crate::enumeration::Enumeration;
+use crate::mixin::Mixin;
+use crate::model_diff::{self, ModelDiff};
+use crate::model_error::{json_position, Location, ModelError, ModelErrorKind};
+use crate::model_format::ModelFormat;
 use crate::object::Object;
 use crate::rust_generator;
 
-use std::collections::BTreeMap;
-use std::error::Error;
-use std::fs::File;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 ///The type of output the generator should create
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
@@ -38,36 +42,131 @@ pub struct ObjectDB {
     ///The directory in which the code will be generated
     rust_destination: String,
     ///The type of output that must be generated
+    #[serde(skip_serializing_if = "Option::is_none")]
     rust_output: Option<RustOutputType>,
+    ///Optional, the directory holding the migrations of the database, one directory per
+    /// migration containing its up.sql and down.sql scripts. The migrations are applied in
+    /// the order of their directory names, and are embedded in the generated code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    migrations: Option<String>,
+    ///Optional, the files defining other objects, enumerations and mixins of the model.
+    /// The paths are relative to the including file and may be glob patterns
+    #[serde(skip_serializing_if = "crate::model_format::is_none_or_empty")]
+    include: Option<Vec<String>>,
     ///The list of objects
+    #[serde(default)]
     objects: Vec<Object>,
+    ///Optional, the list of enumerations that can be used as attribute type
+    #[serde(skip_serializing_if = "crate::model_format::is_none_or_empty")]
+    enums: Option<Vec<Enumeration>>,
+    ///Optional, the list of mixins: sets of attributes shared by multiple objects
+    #[serde(skip_serializing_if = "crate::model_format::is_none_or_empty")]
+    mixins: Option<Vec<Mixin>>,
     ///The mapping between an object's name and the index
     /// of its structured representation in the objects Vec
     #[serde(skip)]
     objects_position: BTreeMap<String, usize>,
+    ///The content of the JSON files of the model, used to retrieve the position
+    /// of the problems found by the validation
+    #[serde(skip)]
+    sources: BTreeMap<String, String>,
+}
+
+///The content of a file included by the model
+#[derive(Deserialize)]
+struct ModelFragment {
+    include: Option<Vec<String>>,
+    objects: Option<Vec<Object>>,
+    enums: Option<Vec<Enumeration>>,
+    mixins: Option<Vec<Mixin>>,
 }
 
 impl ObjectDB {
-    ///Load the object datbase defined as json in a file
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<ObjectDB, Box<dyn Error>> {
-        use serde_json::from_reader;
-        // Open the file in read-only mode.
-        let file = File::open(path)?;
+    ///Load the object datbase defined in a file. The format (JSON, YAML or TOML) is given
+    /// by the extension of the file, JSON being used for the unknown extensions.
+    /// The file may include other files defining objects, enumerations and mixins
+    /// (e.g. `"include": ["billing/*.json"]`)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ObjectDB, ModelError> {
+        let path = path.as_ref();
+        ObjectDB::load_as(path, ModelFormat::from_path(path).unwrap_or(ModelFormat::Json))
+    }
+
+    ///Load the object datbase defined in a file written in the given format. The included
+    /// files are read according to their extension, using the same format by default
+    pub fn load_as<P: AsRef<Path>>(path: P, format: ModelFormat) -> Result<ObjectDB, ModelError> {
+        let path = path.as_ref();
+        let (mut db, content): (ObjectDB, String) = read_model_file(path, format)?;
+
+        // merge the content of all the files, starting with the root file
+        let root = ModelFragment {
+            include: db.include.take(),
+            objects: Some(std::mem::take(&mut db.objects)),
+            enums: db.enums.take(),
+            mixins: db.mixins.take(),
+        };
+        let mut visited = vec![canonicalize(path)?];
+        db.merge(path, format, root, content, &mut visited)?;
+        db.finalize();
+        Ok(db)
+    }
+
+    ///Create a builder, defining the object store programmatically
+    pub fn builder() -> ModelBuilder {
+        ModelBuilder::default()
+    }
 
-        // Read the JSON contents of the file as an instance of object store.
-        let mut db: ObjectDB = from_reader(file)?;
+    ///Create the object store from its elements, as defined by a builder
+    pub(crate) fn from_parts(
+        rust_destination: String,
+        rust_output: Option<RustOutputType>,
+        migrations: Option<String>,
+        objects: Vec<Object>,
+        enums: Vec<Enumeration>,
+        mixins: Vec<Mixin>,
+    ) -> ObjectDB {
+        let mut db = ObjectDB {
+            rust_destination,
+            rust_output,
+            migrations,
+            include: None,
+            objects: Vec::new(),
+            enums: None,
+            mixins: None,
+            objects_position: BTreeMap::new(),
+            sources: BTreeMap::new(),
+        };
+        //the elements are located by their path only, as they are not defined in a file
+        let fragment = ModelFragment {
+            include: None,
+            objects: Some(objects),
+            enums: (!enums.is_empty()).then_some(enums),
+            mixins: (!mixins.is_empty()).then_some(mixins),
+        };
+        db.add_fragment(None, fragment);
+        db.finalize();
+        db
+    }
+
+    ///Compute the information derived from the definitions, once all of them are known:
+    /// the attributes of the mixins, the positions of the objects and the relations
+    fn finalize(&mut self) {
+        // add the attributes of the mixins to the objects including them
+        let mixins = self.mixins.as_deref().unwrap_or_default();
+        for obj in self.objects.iter_mut() {
+            obj.include_mixins(mixins);
+        }
 
         // create the mapping representing the relations
         let mut relations = BTreeMap::<String, Vec<String>>::new();
-        let mut index = 0;
         let mut objects_position = BTreeMap::<String, usize>::new();
-        for obj in &db.objects {
+        for (index, obj) in self.objects.iter().enumerate() {
             objects_position.insert(obj.get_name().clone(), index);
-            index += 1;
             let mut refered_objects = vec![];
             for at in obj.get_attributes() {
                 match at.get_reference() {
-                    Some(r) => refered_objects.push(r.clone()),
+                    //an object may reference the same type through multiple attributes
+                    Some(r) if !refered_objects.contains(r) => refered_objects.push(r.clone()),
+                    Some(_r) => {}
                     None => {}
                 }
             }
@@ -76,21 +175,96 @@ impl ObjectDB {
             }
         }
 
-        db.objects_position = objects_position;
+        self.objects_position = objects_position;
+
+        // the references are stored using the type of primary key of the referenced object
+        let mut id_types = BTreeMap::new();
+        for obj in &self.objects {
+            id_types.insert(obj.get_name().clone(), obj.get_id_type());
+        }
+        for obj in self.objects.iter_mut() {
+            obj.set_reference_id_types(&id_types);
+        }
 
         // fill in the relations
         for (referencing, refereds) in &relations {
             for refered in refereds {
-                let refered_object = match db.get_object_mut(refered) {
+                let refered_object = match self.get_object_mut(refered) {
                     Some(o) => o,
                     None => continue,
                 };
                 refered_object.add_referencing_object(referencing.clone());
             }
         }
+    }
 
-        // Return the object store.
-        Ok(db)
+    ///Add the content of a model file to the object store, then the content of the files it includes.
+    ///The files already visited are skipped, so that a file can be included multiple times
+    fn merge(
+        &mut self,
+        path: &Path,
+        format: ModelFormat,
+        fragment: ModelFragment,
+        content: String,
+        visited: &mut Vec<PathBuf>,
+    ) -> Result<(), ModelError> {
+        let file_name = path.display().to_string();
+        let location = |field: &str, index: usize| Location::new(Some(file_name.clone()), format!("{field}[{index}]"));
+        let includes = self.add_fragment(Some(&file_name), fragment);
+        if format == ModelFormat::Json {
+            self.sources.insert(file_name.clone(), content);
+        }
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        for (index, pattern) in includes.iter().enumerate() {
+            let included_files = resolve_include(directory, pattern).map_err(|e| e.at(&location("include", index)))?;
+            for included in included_files {
+                let canonical = canonicalize(&included).map_err(|e| e.at(&location("include", index)))?;
+                if visited.contains(&canonical) {
+                    continue;
+                }
+                visited.push(canonical);
+                let included_format = ModelFormat::from_path(&included).unwrap_or(format);
+                let (included_fragment, included_content) = read_model_file(&included, included_format)?;
+                self.merge(&included, included_format, included_fragment, included_content, visited)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Add the objects, enumerations and mixins of a fragment, located in the given file, to the
+    /// object store. The included files are returned
+    fn add_fragment(&mut self, file_name: Option<&String>, fragment: ModelFragment) -> Vec<String> {
+        let location = |field: &str, index: usize| Location::new(file_name.cloned(), format!("{field}[{index}]"));
+        for (index, mut obj) in fragment.objects.unwrap_or_default().into_iter().enumerate() {
+            obj.set_location(location("objects", index));
+            self.objects.push(obj);
+        }
+        for (index, mut enumeration) in fragment.enums.unwrap_or_default().into_iter().enumerate() {
+            enumeration.set_location(location("enums", index));
+            self.enums.get_or_insert_with(Vec::new).push(enumeration);
+        }
+        for (index, mut mixin) in fragment.mixins.unwrap_or_default().into_iter().enumerate() {
+            mixin.set_location(location("mixins", index));
+            self.mixins.get_or_insert_with(Vec::new).push(mixin);
+        }
+        fragment.include.unwrap_or_default()
+    }
+
+    ///Set the position of the problem, when it lies in a JSON file of the model
+    fn with_position(&self, error: ModelError) -> ModelError {
+        if error.get_position().is_some() {
+            return error;
+        }
+        let location = error.get_location();
+        let position = location
+            .get_file()
+            .and_then(|file_name| self.sources.get(file_name))
+            .and_then(|content| json_position(content, location.get_path()));
+        match position {
+            Some((line, column)) => error.with_position(line, column),
+            None => error,
+        }
     }
 
     ///Retrieve a mutable reference to an object from its name, if it exists
@@ -105,20 +279,138 @@ impl ObjectDB {
         self.objects.get(index)
     }
 
-    ///Make sure that all referenced objects are existing in this object store
-    pub fn validate(&self) -> Result<(), String> {
-        use std::collections::HashMap;
+    ///Retrieve the enumeration description from its name
+    pub fn get_enum(&self, name: &str) -> Option<&Enumeration> {
+        self.get_enums().find(|e| e.get_name() == name)
+    }
+
+    ///Retrieve the iterator to the different enumerations defined in this object database
+    pub fn get_enums(&self) -> std::slice::Iter<'_, Enumeration> {
+        match &self.enums {
+            Some(enums) => enums.iter(),
+            None => [].iter(),
+        }
+    }
+
+    ///Retrieve the mixin description from its name
+    pub fn get_mixin(&self, name: &str) -> Option<&Mixin> {
+        self.get_mixins().find(|m| m.get_name() == name)
+    }
+
+    ///Retrieve the iterator to the different mixins defined in this object database
+    pub fn get_mixins(&self) -> std::slice::Iter<'_, Mixin> {
+        match &self.mixins {
+            Some(mixins) => mixins.iter(),
+            None => [].iter(),
+        }
+    }
+
+    ///Make sure that all referenced objects, enumerations and mixins are existing in this object store,
+    /// that the names are unique and usable in the generated code and on database, and that the
+    /// definitions are valid.
+    ///All the problems are returned, with the place of the model where they lie
+    pub fn validate(&self) -> Result<(), Vec<ModelError>> {
+        let mut errors = Vec::new();
+        let duplicate = |message: String, location: Location| {
+            ModelError::new(ModelErrorKind::DuplicateName, message).at(&location)
+        };
         let mut objects_map = HashMap::new();
+        let mut tables_map = HashMap::new();
         for obj in &self.objects {
-            objects_map.insert(obj.get_name(), obj);
+            if objects_map.contains_key(obj.get_name()) {
+                errors.push(duplicate(
+                    format!("The object '{object_name}' is defined twice", object_name = obj.get_name()),
+                    obj.get_location().child("name"),
+                ));
+            } else {
+                objects_map.insert(obj.get_name(), obj);
+            }
+            match tables_map.get(obj.get_table_name()) {
+                Some(other) => errors.push(duplicate(
+                    format!(
+                        "The table '{table_name}' is used both by '{other_name}' and by '{object_name}'",
+                        table_name = obj.get_table_name(),
+                        other_name = other,
+                        object_name = obj.get_name()
+                    ),
+                    obj.get_location().child("tbnm"),
+                )),
+                None => {
+                    tables_map.insert(obj.get_table_name(), obj.get_name());
+                }
+            }
+        }
+        let mut enums_map = HashMap::new();
+        for enumeration in self.get_enums() {
+            errors.extend(enumeration.validate());
+            let location = enumeration.get_location().child("name");
+            if objects_map.contains_key(enumeration.get_name()) {
+                errors.push(duplicate(
+                    format!(
+                        "The name '{enum_name}' is used both by an object and an enumeration",
+                        enum_name = enumeration.get_name()
+                    ),
+                    location,
+                ));
+            } else if enums_map.contains_key(enumeration.get_name()) {
+                errors.push(duplicate(
+                    format!("The enumeration '{enum_name}' is defined twice", enum_name = enumeration.get_name()),
+                    location,
+                ));
+            } else {
+                enums_map.insert(enumeration.get_name(), enumeration);
+            }
+        }
+        let mut mixins_map = HashMap::new();
+        for mixin in self.get_mixins() {
+            errors.extend(mixin.validate());
+            let location = mixin.get_location().child("name");
+            if objects_map.contains_key(mixin.get_name()) || enums_map.contains_key(mixin.get_name()) {
+                errors.push(duplicate(
+                    format!(
+                        "The name '{mixin_name}' is used both by a mixin and an object or enumeration",
+                        mixin_name = mixin.get_name()
+                    ),
+                    location,
+                ));
+            } else if mixins_map.contains_key(mixin.get_name()) {
+                errors.push(duplicate(
+                    format!("The mixin '{mixin_name}' is defined twice", mixin_name = mixin.get_name()),
+                    location,
+                ));
+            } else {
+                mixins_map.insert(mixin.get_name(), mixin);
+            }
         }
         for obj in &self.objects {
-            match obj.validate(&objects_map) {
-                Ok(_) => {}
-                Err(e) => return Err(e),
+            errors.extend(obj.validate(&objects_map, &enums_map, &mixins_map));
+            errors.extend(rust_generator::validate_function_names(obj, self));
+        }
+        errors.extend(rust_generator::validate_module_names(self));
+        if errors.is_empty() {
+            return Ok(());
+        }
+        //the problems of a mixin are reported once, even if it is included by multiple objects
+        let mut reported: Vec<ModelError> = Vec::with_capacity(errors.len());
+        for error in errors {
+            let error = self.with_position(error);
+            if !reported.contains(&error) {
+                reported.push(error);
             }
         }
-        Ok(())
+        Err(reported)
+    }
+
+    ///Compute the changes from this version of the model to a new one, each change being
+    /// classified as compatible or breaking for the existing databases and for the code
+    /// calling the generated rust code
+    pub fn diff(&self, new: &ObjectDB) -> ModelDiff {
+        model_diff::diff_models(self, new)
+    }
+
+    ///Retrieve the directory holding the migrations of the database, if any
+    pub fn get_migrations(&self) -> Option<&String> {
+        self.migrations.as_ref()
     }
 
     ///Retrieve the iterator to the different objects defined in this object database
@@ -141,3 +433,298 @@ impl ObjectDB {
         }
     }
 }
+
+///Read a model file written in the given format, returning its content along with the read value
+fn read_model_file<T: serde::de::DeserializeOwned>(path: &Path, format: ModelFormat) -> Result<(T, String), ModelError> {
+    let file_name = path.display().to_string();
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(ModelError::new(ModelErrorKind::Io, format!("Unable to open '{file_name}': {e}")).in_file(&file_name))
+        }
+    };
+    match format.parse(&content) {
+        Ok(value) => Ok((value, content)),
+        Err(e) => Err(e.in_file(&file_name)),
+    }
+}
+
+///Retrieve the absolute path of a file of the model, which must exist
+fn canonicalize(path: &Path) -> Result<PathBuf, ModelError> {
+    path.canonicalize().map_err(|e| {
+        ModelError::new(ModelErrorKind::Io, format!("Unable to open '{}': {e}", path.display()))
+    })
+}
+
+///Retrieve the files matching an include entry, relative to the directory of the including file.
+///A glob pattern may match no file, while a path must designate an existing file
+fn resolve_include(directory: &Path, pattern: &str) -> Result<Vec<PathBuf>, ModelError> {
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![directory.join(pattern)]);
+    }
+    //the directory is escaped, so that only the include entry is considered as a pattern
+    let full_pattern = if directory.as_os_str().is_empty() || Path::new(pattern).is_absolute() {
+        pattern.to_string()
+    } else {
+        format!("{}/{pattern}", glob::Pattern::escape(&directory.to_string_lossy()))
+    };
+    let entries = glob::glob(&full_pattern).map_err(|e| {
+        ModelError::new(ModelErrorKind::Syntax, format!("Invalid include pattern '{pattern}': {e}"))
+    })?;
+    let mut files = Vec::new();
+    for entry in entries {
+        files.push(entry.map_err(|e| ModelError::new(ModelErrorKind::Io, e.to_string()))?);
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectDB;
+    use crate::{convert_model_file, ModelErrorKind, ModelFormat};
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Write the files of a model in a new temporary directory
+    fn write_model(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("bdmg2k_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        for (file_name, content) in files {
+            let path = directory.join(file_name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        directory
+    }
+
+    #[test]
+    fn includes() {
+        let directory = write_model(
+            "includes",
+            &[
+                ("model.json", "{\"rust_destination\": \"out\", \"include\": [\"parts/*.json\", \"enums.json\"]}"),
+                ("enums.json", "{\"enums\": [{\"name\": \"Color\", \"variants\": [{\"name\": \"Red\"}]}]}"),
+                ("parts/a.json", "{\"include\": [\"../enums.json\"], \"objects\": [{\"tbnm\": \"cars\", \"name\": \"Car\", \"attr\": [
+                    {\"name\": \"color\", \"is\": {\"Mandatory\": {\"Enum\": \"Color\"}}},
+                    {\"name\": \"owner\", \"is\": {\"Mandatory\": {\"Reference\": \"Person\"}}}]}]}"),
+                ("parts/b.json", "{\"objects\": [{\"tbnm\": \"people\", \"name\": \"Person\", \"attr\": [
+                    {\"name\": \"size\", \"is\": {\"Mandatory\": {\"Enum\": \"Size\"}}}]}]}"),
+            ],
+        );
+        let db = ObjectDB::load(directory.join("model.json")).unwrap();
+        let names: Vec<&String> = db.get_objects().map(|o| o.get_name()).collect();
+        assert_eq!(vec!["Car", "Person"], names);
+        assert_eq!(1, db.get_enums().count());
+        assert!(db.get_object("Person").unwrap().is_referenced());
+        //the error is reported with the file and the place defining the attribute
+        let errors = db.validate().unwrap_err();
+        assert_eq!(1, errors.len());
+        assert_eq!(ModelErrorKind::UnknownReference, errors[0].get_kind());
+        let file_name = directory.join("parts/b.json").display().to_string();
+        assert_eq!(Some(&file_name), errors[0].get_location().get_file());
+        assert_eq!("objects[0].attr[0].is", errors[0].get_location().get_path());
+        assert_eq!(Some((2, 38)), errors[0].get_position());
+        assert!(errors[0].to_string().starts_with(&format!("{file_name}:2:38: Unknown enumeration 'Size'")));
+        let _ = fs::remove_dir_all(&directory);
+
+        let directory = write_model(
+            "duplicates",
+            &[
+                ("model.json", "{\"rust_destination\": \"out\", \"include\": [\"other.json\"], \"objects\": [{\"tbnm\": \"cars\", \"name\": \"Car\", \"attr\": []}]}"),
+                ("other.json", "{\"objects\": [{\"tbnm\": \"cars\", \"name\": \"Vehicle\", \"attr\": []}]}"),
+            ],
+        );
+        let errors = ObjectDB::load(directory.join("model.json")).unwrap().validate().unwrap_err();
+        assert_eq!(ModelErrorKind::DuplicateName, errors[0].get_kind());
+        assert!(errors[0].get_message().starts_with("The table 'cars' is used both by 'Car'"));
+        assert_eq!("objects[0].tbnm", errors[0].get_location().get_path());
+        let error = ObjectDB::load(directory.join("missing.json")).unwrap_err();
+        assert_eq!(ModelErrorKind::Io, error.get_kind());
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn errors() {
+        let directory = write_model(
+            "errors",
+            &[
+                ("model.json", "{\"rust_destination\": \"out\", \"objects\": [
+  {\"tbnm\": \"my cars\", \"name\": \"Car\", \"mixins\": [\"Named\"], \"attr\": [
+    {\"name\": \"owner\", \"is\": {\"Mandatory\": {\"Reference\": \"Person\"}}},
+    {\"name\": \"owner\", \"is\": {\"Mandatory\": \"String\"}}
+  ]}
+], \"enums\": [{\"name\": \"Car\", \"variants\": [{\"name\": \"1st\"}]}]}"),
+                ("broken.json", "{\"rust_destination\": \"out\",\n \"objects\": [}"),
+            ],
+        );
+        //all the problems are reported at once
+        let errors = ObjectDB::load(directory.join("model.json")).unwrap().validate().unwrap_err();
+        let found = errors
+            .iter()
+            .map(|e| (e.get_kind(), e.get_location().get_path().as_str(), e.get_position()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (ModelErrorKind::InvalidIdentifier, "enums[0].variants[0].name", Some((6, 44))),
+                (ModelErrorKind::DuplicateName, "enums[0].name", Some((6, 15))),
+                (ModelErrorKind::InvalidIdentifier, "objects[0].tbnm", Some((2, 4))),
+                (ModelErrorKind::UnknownReference, "objects[0].mixins[0]", Some((2, 49))),
+                (ModelErrorKind::UnknownReference, "objects[0].attr[0].is", Some((3, 23))),
+                (ModelErrorKind::DuplicateName, "objects[0].attr[1]", Some((4, 5))),
+            ],
+            found
+        );
+
+        let error = ObjectDB::load(directory.join("broken.json")).unwrap_err();
+        assert_eq!(ModelErrorKind::Syntax, error.get_kind());
+        assert_eq!(Some((2, 14)), error.get_position());
+        assert!(error.to_string().ends_with("broken.json:2:14: expected value"));
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn module_names() {
+        let directory = write_model(
+            "modules",
+            &[("model.json", "{\"rust_destination\": \"out\", \"objects\": [
+  {\"tbnm\": \"schemas\", \"name\": \"Schema\", \"attr\": []},
+  {\"tbnm\": \"books\", \"name\": \"Book\", \"attr\": []},
+  {\"tbnm\": \"other_books\", \"name\": \"BOOK\", \"attr\": []}
+], \"enums\": [{\"name\": \"BooK\", \"variants\": [{\"name\": \"Novel\"}]}]}")],
+        );
+        let errors = ObjectDB::load(directory.join("model.json")).unwrap().validate().unwrap_err();
+        let messages: Vec<&String> = errors.iter().map(|e| e.get_message()).collect();
+        assert_eq!(
+            vec![
+                "The name 'Schema' is generated in the module 'schema', which holds the diesel schema",
+                "The names 'Book' and 'BOOK' are both generated in the module 'book'",
+                "The names 'Book' and 'BooK' are both generated in the module 'book'",
+            ],
+            messages
+        );
+        assert_eq!(ModelErrorKind::DuplicateName, errors[1].get_kind());
+        assert_eq!("objects[2].name", errors[1].get_location().get_path());
+        assert_eq!("enums[0].name", errors[2].get_location().get_path());
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn reserved_names() {
+        let directory = write_model(
+            "reserved",
+            &[("model.json", "{\"rust_destination\": \"out\", \"objects\": [
+  {\"tbnm\": \"group\", \"name\": \"Post\", \"attr\": [
+    {\"name\": \"type\", \"is\": {\"Mandatory\": \"String\"}},
+    {\"name\": \"Order\", \"is\": {\"Mandatory\": \"Integer\"}},
+    {\"name\": \"version\", \"is\": {\"Mandatory\": \"Integer\"}},
+    {\"name\": \"tags\", \"is\": {\"Mandatory\": \"String\"}}
+  ]},
+  {\"tbnm\": \"tags\", \"name\": \"Tag\", \"attr\": [
+    {\"name\": \"post\", \"is\": {\"Mandatory\": {\"Reference\": \"Post\"}}},
+    {\"name\": \"post_id\", \"is\": {\"Mandatory\": \"Integer\"}}
+  ]}
+]}")],
+        );
+        let errors = ObjectDB::load(directory.join("model.json")).unwrap().validate().unwrap_err();
+        let messages: Vec<&String> = errors.iter().map(|e| e.get_message()).collect();
+        assert_eq!(
+            vec![
+                "The table name 'group' is not a valid identifier: it is a keyword of SQL",
+                "The attribute name 'type' is not a valid identifier: it is a keyword of Rust",
+                "The attribute name 'Order' is not a valid identifier: it is a keyword of SQL",
+                "The attribute name 'version' of 'Post' is used by a generated column",
+                "The function 'get_tags' of 'Post' is generated both for the attribute 'tags' and for the reference 'Tag.post'",
+                "The function 'get_post_id' of 'Tag' is generated both for the attribute 'post' and for the attribute 'post_id'",
+            ],
+            messages
+        );
+        assert_eq!("objects[1].attr[0]", errors[4].get_location().get_path());
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn formats() {
+        let directory = write_model(
+            "formats",
+            &[
+                ("model.yaml", "# the generated code
+rust_destination: out
+include: [cars.toml]
+enums:
+  - name: Color
+    variants:
+      - name: Red
+"),
+                ("cars.toml", "# the cars
+[[objects]]
+tbnm = \"cars\"
+name = \"Car\"
+
+[[objects.attr]]
+name = \"color\"
+is = { Mandatory = { Enum = \"Color\" } }
+"),
+            ],
+        );
+        let db = ObjectDB::load(directory.join("model.yaml")).unwrap();
+        assert!(db.validate().is_ok());
+        assert_eq!(1, db.get_object("Car").unwrap().get_attributes().count());
+        assert!(ObjectDB::load_as(directory.join("model.yaml"), ModelFormat::Json).is_err());
+
+        //the converted files define the same model
+        convert_model_file(&directory.join("cars.toml"), &directory.join("cars.json")).unwrap();
+        convert_model_file(&directory.join("cars.json"), &directory.join("cars.yml")).unwrap();
+        convert_model_file(&directory.join("model.yaml"), &directory.join("model.toml")).unwrap();
+        let content = std::fs::read_to_string(directory.join("cars.json")).unwrap();
+        assert!(content.find("tbnm").unwrap() < content.find("attr").unwrap());
+        let db = ObjectDB::load(directory.join("model.toml")).unwrap();
+        assert_eq!(1, db.get_object("Car").unwrap().get_attributes().count());
+        assert_eq!(
+            ModelFormat::Json.parse::<serde_json::Value>(&content).unwrap(),
+            ModelFormat::Yaml
+                .parse::<serde_json::Value>(&std::fs::read_to_string(directory.join("cars.yml")).unwrap())
+                .unwrap()
+        );
+        assert!(convert_model_file(&directory.join("cars.json"), &directory.join("cars.txt")).is_err());
+
+        //the converted YAML file is read back as a model
+        fs::write(directory.join("converted.json"), "{\"rust_destination\": \"out\", \"include\": [\"cars.yml\"], \"enums\": [{\"name\": \"Color\", \"variants\": [{\"name\": \"Red\"}]}]}").unwrap();
+        let db = ObjectDB::load(directory.join("converted.json")).unwrap();
+        assert!(db.validate().is_ok());
+        assert_eq!(1, db.get_object("Car").unwrap().get_attributes().count());
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn yaml_objects() {
+        let directory = write_model(
+            "yaml_objects",
+            &[("model.yml", "# the generated code
+rust_destination: out
+objects:
+  - tbnm: owners
+    name: Owner
+    attr:
+      - name: name
+        is: {Mandatory: String}
+  - tbnm: pets
+    name: Pet
+    attr:
+      - name: owner
+        is:
+          Optional:
+            Reference: Owner
+      - name: weight
+        is: {Mandatory: {Decimal: {precision: 5, scale: 2}}}
+")],
+        );
+        let db = ObjectDB::load(directory.join("model.yml")).unwrap();
+        assert!(db.validate().is_ok());
+        let pet = db.get_object("Pet").unwrap();
+        assert_eq!(Some(&String::from("Owner")), pet.get_attributes().next().unwrap().get_reference());
+        assert!(db.get_object("Owner").unwrap().is_referenced());
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,105 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::enumeration::Enumeration;
+
+/// Generate the rust enum representing the enumeration, with the implementation of
+/// FromStr, Display and the diesel conversions from and to a Text column
+pub fn generate_rust_enum(enumeration: &Enumeration) -> String {
+    let enum_name = enumeration.get_name();
+    let mut variants = String::new();
+    let mut variant_list = String::new();
+    let mut to_str = String::new();
+    let mut from_str = String::new();
+    for variant in enumeration.get_variants() {
+        if let Some(comm) = variant.get_comment() {
+            variants = format!(
+                "{variants}    /// {}\n",
+                comm.replace("\r\n", "\n").replace('\n', "\n    /// ")
+            );
+        }
+        let variant_name = variant.get_name();
+        variants = format!("{variants}    {variant_name},\n");
+        variant_list = format!("{variant_list}{enum_name}::{variant_name}, ");
+        to_str = format!("{to_str}            {enum_name}::{variant_name} => \"{variant_name}\",\n");
+        from_str = format!("{from_str}            \"{variant_name}\" => Ok({enum_name}::{variant_name}),\n");
+    }
+
+    let comment = match enumeration.get_comment() {
+        Some(comments) => comments.replace("\r\n", "\n").replace('\n', "\n/// "),
+        None => String::new(),
+    };
+
+    format!(
+        "use diesel::deserialize::FromSql;
+use diesel::serialize::{{IsNull, Output, ToSql}};
+use diesel::sql_types::Text;
+use diesel::sqlite::{{Sqlite, SqliteValue}};
+
+/// {comment}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = Text)]
+pub enum {enum_name} {{
+{variants}}}
+
+impl {enum_name} {{
+    /// Retrieve all the values of {enum_name}
+    pub fn variants() -> &'static [{enum_name}] {{
+        &[{variant_list}]
+    }}
+
+    /// Retrieve the name of the value, as stored on database
+    pub fn as_str(&self) -> &'static str {{
+        match self {{
+{to_str}        }}
+    }}
+}}
+
+impl std::fmt::Display for {enum_name} {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        write!(f, \"{{}}\", self.as_str())
+    }}
+}}
+
+impl std::str::FromStr for {enum_name} {{
+    type Err = bdmg::ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        match s {{
+{from_str}            _ => Err(bdmg::ParseEnumError::new(\"{enum_name}\", s)),
+        }}
+    }}
+}}
+
+impl ToSql<Text, Sqlite> for {enum_name} {{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {{
+        out.set_value(self.as_str());
+        Ok(IsNull::No)
+    }}
+}}
+
+impl FromSql<Text, Sqlite> for {enum_name} {{
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> diesel::deserialize::Result<Self> {{
+        let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(value.parse::<{enum_name}>()?)
+    }}
+}}
+"
+    )
+}
@@ -22,36 +22,44 @@ use crate::object::Object;
 pub fn generate_rust_free_functions(object: &Object) -> String {
     format!("\n///function used in the ObjectIterator to retrieve the next instance
 fn retrieve_next_{object_lowercase}_object<'a>(
-    current_id: i32,
-    last_id: i32,
+    current_id: bdmg::ObjectId,
+    last_id: bdmg::ObjectId,
     connection: &'a mut diesel::sqlite::SqliteConnection,
-) -> (i32, Option<Result<Box<(dyn Object + 'static)>, String>>) {{
-    if last_id < current_id {{
-        return (current_id, None);
+) -> (Option<bdmg::ObjectId>, Option<Result<Box<(dyn Object + 'static)>, String>>) {{
+    let (current, last) = match (<{id_type}>::try_from(current_id), <{id_type}>::try_from(last_id)) {{
+        (Ok(current), Ok(last)) => (current, last),
+        (Err(e), _) | (_, Err(e)) => return (None, Some(Err(format!(\"Unable to iterate on {object_name}: {{}}\", e)))),
+    }};
+    if last < current {{
+        return (None, None);
     }}
-    let loaded_result = {object_name}::load(connection, current_id);
+    let loaded_result = {object_name}::load(connection, current);
     let next_id = match {table_name}::dsl::{table_name}.select({table_name}::id)
-                         .filter({table_name}::id.gt(current_id))
+                         .filter({table_name}::id.gt(current))
                          .order({table_name}::id.asc())
                          .limit(1)
-                         .get_result::<i32>(connection) {{
-        Ok(v) => v,
-        Err(diesel::result::Error::NotFound) => last_id + 1,
+                         .get_result::<{id_type}>(connection) {{
+        Ok(v) => Some(bdmg::ObjectId::from(v)),
+        Err(diesel::result::Error::NotFound) => None,
         Err(e) => {{
-            return (current_id+1, Some(Err(format!(\"Unable to retrieve the next id for {object_name}:\\n{{}}\", e))));
+            return (None, Some(Err(format!(\"Unable to retrieve the next id for {object_name}:\\n{{}}\", e))));
         }}
     }};
     match loaded_result {{
         Ok(v) => (next_id, Some(Ok(Box::new(v)))),
-        Err(e) => match retrieve_next_{object_lowercase}_object(next_id, last_id, connection) {{
-            (_, Some(Err(ee))) => (last_id + 1, Some(Err(format!(\"Unable to load the next {object_name}: {{}}\\n{{}}\", e, ee)))),
-            (_, None) => (last_id + 1, None),
-            (future_id, Some(Ok(v))) => (future_id, Some(Ok(v))),
+        Err(e) => match next_id {{
+            None => (None, None),
+            Some(next_id) => match retrieve_next_{object_lowercase}_object(next_id, last_id, connection) {{
+                (_, Some(Err(ee))) => (None, Some(Err(format!(\"Unable to load the next {object_name}: {{}}\\n{{}}\", e, ee)))),
+                (_, None) => (None, None),
+                (future_id, Some(Ok(v))) => (future_id, Some(Ok(v))),
+            }}
         }}
     }}
 }}",
         object_lowercase =object.get_name().to_ascii_lowercase(), 
         object_name = object.get_name(),
-        table_name = object.get_table_name()
+        table_name = object.get_table_name(),
+        id_type = super::get_id_type(object.get_id_type()),
     )
 }
\ No newline at end of file
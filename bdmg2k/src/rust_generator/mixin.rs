@@ -0,0 +1,187 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::mixin::Mixin;
+use crate::object::Object;
+use crate::{Attribute, AttributeType};
+
+/// Generate the rust trait giving access to the attributes of the mixin. The trait
+/// contains the getters of every attribute and the setters of the mutable ones
+pub fn generate_rust_mixin(mixin: &Mixin) -> String {
+    let mut imports = String::new();
+    let mut functions = String::new();
+    let mut uses_connection = false;
+    let mut imported: Vec<&String> = Vec::new();
+    for at in mixin.get_attributes() {
+        match (at.get_reference(), at.get_enum()) {
+            (Some(r), _) if !imported.contains(&r) => {
+                imported.push(r);
+                imports = format!(
+                    "{imports}use super::{r};\nuse super::{module}::Id{r};\n",
+                    module = r.to_lowercase()
+                );
+            }
+            (_, Some(e)) if !imported.contains(&e) => {
+                imported.push(e);
+                imports = format!("{imports}use super::{e};\n");
+            }
+            _ => {}
+        }
+        for (comment, signature, _call) in get_mixin_functions(at) {
+            uses_connection = uses_connection || signature.contains("connection");
+            functions = format!("{functions}    {comment}\n    {signature};\n");
+        }
+    }
+    if uses_connection {
+        imports = format!("use diesel::sqlite::SqliteConnection;\n{imports}");
+    }
+
+    let comment = match mixin.get_comment() {
+        Some(comments) => comments.replace("\r\n", "\n").replace('\n', "\n/// "),
+        None => format!("The objects sharing the attributes of {}", mixin.get_name()),
+    };
+
+    format!(
+        "{imports}
+/// {comment}
+pub trait {mixin_name} {{
+{functions}}}
+",
+        mixin_name = mixin.get_name()
+    )
+}
+
+/// Generate the implementation of the traits of the mixins included by the object,
+/// calling the functions of the object
+pub fn generate_rust_mixins_impl(object: &Object, mixins: &[&Mixin]) -> String {
+    let mut code = String::new();
+    for mixin in mixins {
+        let mut functions = String::new();
+        for at in mixin.get_attributes() {
+            for (_comment, signature, call) in get_mixin_functions(at) {
+                functions = format!(
+                    "{functions}    {signature} {{\n        {object_name}::{call}\n    }}\n",
+                    object_name = object.get_name()
+                );
+            }
+        }
+        code = format!(
+            "{code}\nimpl {mixin_name} for {object_name} {{\n{functions}}}\n",
+            mixin_name = mixin.get_name(),
+            object_name = object.get_name()
+        );
+    }
+    code
+}
+
+/// Retrieve the comment, the signature and the call of the functions giving access
+/// to the attribute: the same as the ones generated for the objects
+fn get_mixin_functions(attribute: &Attribute) -> Vec<(String, String, String)> {
+    let name = attribute.get_name();
+    let desc = match attribute.get_comment() {
+        Some(comm) => format!("\n    /// {comm}"),
+        None => String::new(),
+    };
+    let comment = format!("/// Retrieve the value of the {name} attribute.{desc}");
+    let mut functions = match attribute.get_reference() {
+        Some(r) => {
+            let (result, id_result) = match attribute.get_type() {
+                AttributeType::Mandatory(_) => {
+                    (format!("Result<{r}, bdmg::Error>"), format!("Id{r}"))
+                }
+                AttributeType::Optional(_) => (format!("Option<{r}>"), format!("Option<Id{r}>")),
+            };
+            vec![
+                (
+                    comment.clone(),
+                    format!("fn get_{name}(&self, connection: &mut SqliteConnection) -> {result}"),
+                    format!("get_{name}(self, connection)"),
+                ),
+                (
+                    comment,
+                    format!("fn get_{name}_id(&self) -> {id_result}"),
+                    format!("get_{name}_id(self)"),
+                ),
+            ]
+        }
+        None => {
+            let borrow = if super::is_copy_type(attribute.get_type().get_base_type()) {
+                ""
+            } else {
+                "&"
+            };
+            vec![(
+                comment,
+                format!(
+                    "fn get_{name}(&self) -> {borrow}{}",
+                    super::get_rust_type(attribute)
+                ),
+                format!("get_{name}(self)"),
+            )]
+        }
+    };
+    if attribute.is_mutable() {
+        functions.push((
+            format!("/// Set the value of the {name} attribute.{desc}"),
+            format!(
+                "fn set_{name}(&mut self, attr_value: {}, connection: &mut SqliteConnection) -> Result<(), bdmg::Error>",
+                super::get_rust_param_type(attribute)
+            ),
+            format!("set_{name}(self, attr_value, connection)"),
+        ));
+    }
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mixin::Mixin;
+
+    use super::generate_rust_mixin;
+
+    #[test]
+    fn trait_definition() {
+        let mixin: Mixin = serde_json::from_slice(
+            b"{\"name\": \"Named\", \"attr\": [
+                {\"name\": \"name\", \"is\": {\"Mandatory\": \"String\"}, \"mutable\": true, \"comm\": \"The display name\"},
+                {\"name\": \"rank\", \"is\": {\"Optional\": \"Integer\"}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "use diesel::sqlite::SqliteConnection;
+
+/// The objects sharing the attributes of Named
+pub trait Named {
+    /// Retrieve the value of the name attribute.
+    /// The display name
+    fn get_name(&self) -> &String;
+    /// Set the value of the name attribute.
+    /// The display name
+    fn set_name(&mut self, attr_value: String, connection: &mut SqliteConnection) -> Result<(), bdmg::Error>;
+    /// Retrieve the value of the rank attribute.
+    fn get_rank(&self) -> Option<i64>;
+}
+"
+            ),
+            generate_rust_mixin(&mixin)
+        );
+    }
+}
@@ -17,22 +17,80 @@
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
 
+mod enumeration;
 mod free_fn;
+mod mixin;
 mod rust_impl;
+mod schema;
 mod traits_impl;
 
+use crate::enumeration::Enumeration;
+use crate::mixin::Mixin;
+use crate::model_error::{Location, ModelError, ModelErrorKind};
 use crate::object::Object;
 use crate::objectdb::{ObjectDB, RustOutputType};
-use crate::{Attribute, AttributeType, BaseAttributeType, Error};
+use crate::sqlite_generator;
+use crate::{Attribute, AttributeType, BaseAttributeType, Error, IdentifierType};
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 
+use self::enumeration::generate_rust_enum;
 use self::free_fn::generate_rust_free_functions;
+use self::mixin::{generate_rust_mixin, generate_rust_mixins_impl};
 use self::rust_impl::generate_rust_impl;
+pub(crate) use self::rust_impl::validate_function_names;
+use self::schema::generate_schema;
 use self::traits_impl::generate_traits_impl;
 
+///Retrieve the name of the module, and of the file, holding the code of an object, enumeration or mixin
+fn module_name(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
+
+/// Check that the objects, enumerations and mixins are generated in different modules, e.g.
+/// that `Book` and `BOOK` are not both written in `book.rs`, and that none of them is
+/// written in the module of the diesel schema
+pub(crate) fn validate_module_names(db: &ObjectDB) -> Vec<ModelError> {
+    let mut elements: Vec<(&String, &Location)> = Vec::new();
+    elements.extend(db.get_objects().map(|obj| (obj.get_name(), obj.get_location())));
+    elements.extend(db.get_enums().map(|enumeration| (enumeration.get_name(), enumeration.get_location())));
+    elements.extend(db.get_mixins().map(|mixin| (mixin.get_name(), mixin.get_location())));
+
+    let mut errors = Vec::new();
+    let mut modules: HashMap<String, &String> = HashMap::new();
+    for (name, location) in elements {
+        let module = module_name(name);
+        if module == "schema" {
+            errors.push(
+                ModelError::new(
+                    ModelErrorKind::DuplicateName,
+                    format!("The name '{name}' is generated in the module 'schema', which holds the diesel schema"),
+                )
+                .at(&location.child("name")),
+            );
+            continue;
+        }
+        match modules.get(&module) {
+            //the names defined twice are already reported by the object database
+            Some(other) if *other == name => {}
+            Some(other) => errors.push(
+                ModelError::new(
+                    ModelErrorKind::DuplicateName,
+                    format!("The names '{other}' and '{name}' are both generated in the module '{module}'"),
+                )
+                .at(&location.child("name")),
+            ),
+            None => {
+                modules.insert(module, name);
+            }
+        }
+    }
+    errors
+}
+
 fn get_lib_file(mut destination: PathBuf) -> Result<(File, String), Error> {
     if !destination.is_dir() {
         return Err(Error::DestinationIsNotDirectory {
@@ -92,13 +150,41 @@ fn get_mod_file(mut destination: PathBuf) -> Result<(File, String), Error> {
     }
 }
 
+///Retrieve the file that should contain the diesel schema
+///
+/// The file will be located inside of the destination that is given as parameter
+fn get_schema_file(mut destination: PathBuf) -> Result<(File, String), Error> {
+    if !destination.is_dir() {
+        return Err(Error::DestinationIsNotDirectory {
+            destination: match destination.to_str() {
+                Some(p) => String::from(p),
+                None => String::from("UNKNOWN"),
+            },
+        });
+    }
+
+    destination.push("schema");
+    destination.set_extension("rs");
+
+    let filename = match destination.as_path().to_str() {
+        Some(pth) => String::from(pth),
+        None => String::from("schema.rs"),
+    };
+
+    match File::create(destination.as_path()) {
+        Err(_e) => Err(Error::UnableToCreateFile { file: filename }),
+        Ok(f) => Ok((f, filename)),
+    }
+}
+
 ///Retrieve the file that will contain the rust code related to the given object
+/// or enumeration
 ///
 /// # Error
 /// An error will be returned in the following cases:
 /// - the path is not a directory
 /// - we were not able to create the corresponding file
-fn get_object_file<'a>(object: &Object, path: &'a Path) -> Result<(File, String), Error> {
+fn get_object_file(name: &str, path: &Path) -> Result<(File, String), Error> {
     if !path.is_dir() {
         return Err(Error::DestinationIsNotDirectory {
             destination: match path.to_str() {
@@ -108,7 +194,7 @@ fn get_object_file<'a>(object: &Object, path: &'a Path) -> Result<(File, String)
         });
     }
     let mut pbuf = PathBuf::from(path);
-    let lowercase = object.get_name().to_ascii_lowercase();
+    let lowercase = module_name(name);
     pbuf.push(&lowercase);
     pbuf.set_extension("rs");
 
@@ -147,10 +233,26 @@ fn get_mod_file_content(objects: &ObjectDB) -> Result<String, Error> {
         usings = usings
             + &format!(
                 "mod {module_name};\npub use {module_name}::{object_name};\npub use {module_name}::Id{object_name};\n",
-                module_name = obj.get_name().to_ascii_lowercase(),
+                module_name = module_name(obj.get_name()),
                 object_name = obj.get_name()
             );
     }
+    for enumeration in objects.get_enums() {
+        usings = usings
+            + &format!(
+                "mod {module_name};\npub use {module_name}::{enum_name};\n",
+                module_name = module_name(enumeration.get_name()),
+                enum_name = enumeration.get_name()
+            );
+    }
+    for mixin in objects.get_mixins() {
+        usings = usings
+            + &format!(
+                "mod {module_name};\npub use {module_name}::{mixin_name};\n",
+                module_name = module_name(mixin.get_name()),
+                mixin_name = mixin.get_name()
+            );
+    }
     usings += "pub mod schema;\n";
 
     let mut inserts = String::new();
@@ -168,7 +270,7 @@ fn get_mod_file_content(objects: &ObjectDB) -> Result<String, Error> {
         (obj_intro, attributes)
     }});",
                 object_name = obj.get_name(),
-                object_module = obj.get_name().to_ascii_lowercase()
+                object_module = module_name(obj.get_name())
             );
     }
 
@@ -183,7 +285,54 @@ pub fn get_objects() -> std::collections::BTreeMap<String, (Box<dyn ObjectIntros
     objects
 }}
 ");
-    Ok(format!("{usings}{content}\n"))
+    let migrations = generate_rust_migrations(objects)?;
+    Ok(format!("{usings}{content}{migrations}\n"))
+}
+
+/// Generate the migrations of the database, embedded in the module, and the function
+/// applying them. Nothing is generated if the model does not define any migration directory
+fn generate_rust_migrations(objects: &ObjectDB) -> Result<String, Error> {
+    let directory = match objects.get_migrations() {
+        Some(directory) => directory,
+        None => return Ok(String::new()),
+    };
+    let mut migrations = String::new();
+    for (name, up, down) in sqlite_generator::read_migrations(directory)? {
+        migrations += &format!(
+            "
+    bdmg::Migration::new(
+        {name:?},
+        {up},
+        {down},
+    ),",
+            up = get_rust_string_literal(&up),
+            down = get_rust_string_literal(&down)
+        );
+    }
+    Ok(format!(
+        "
+///The migrations of the database, in the order they must be applied. The version of the
+/// model reached by a migration is its position in the list, starting at 1
+pub const MIGRATIONS: &[bdmg::Migration] = &[{migrations}
+];
+
+///Apply the migrations that are not yet applied to the database. They are refused if the
+/// database was migrated to a version of the model more recent than this one
+pub fn run_migrations(connection: &mut diesel::sqlite::SqliteConnection) -> Result<Vec<&'static bdmg::Migration>, bdmg::Error> {{
+    bdmg::run_migrations(connection, MIGRATIONS)
+}}
+"
+    ))
+}
+
+/// Retrieve the raw string literal containing the given text, using enough hashes to
+/// delimit it
+fn get_rust_string_literal(text: &str) -> String {
+    let mut hashes = String::from("#");
+    while text.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{text}\"{hashes}")
 }
 
 /// Retrieve the rust representation of the object
@@ -232,6 +381,15 @@ pub fn generate_code(
     for object in objects.get_objects() {
         get_object_mod_file_content(object, objects, path)?;
     }
+    for enumeration in objects.get_enums() {
+        write_enum(enumeration, path)?;
+    }
+    for mixin in objects.get_mixins() {
+        write_mixin(mixin, path)?;
+    }
+
+    //then, the diesel schema used by all objects
+    write_schema(objects, pbuf.clone())?;
 
     //second, let us generate the mod file to have all object structs public
     let (file_content, (mut file, filename)) = if output_type == RustOutputType::Module {
@@ -257,19 +415,60 @@ pub fn generate_code(
     }
 }
 
+///Write the rust enum representing the enumeration
+fn write_enum(enumeration: &Enumeration, path: &Path) -> Result<(), Error> {
+    let file_content = generate_rust_enum(enumeration);
+    let (mut file, filename) = get_object_file(enumeration.get_name(), path)?;
+    match file.write_all(file_content.as_bytes()) {
+        Err(_e) => Err(Error::UnableToWriteToFile {
+            file: filename,
+            content: file_content,
+        }),
+        Ok(()) => Ok(()),
+    }
+}
+
+///Write the rust trait giving access to the attributes of the mixin
+fn write_mixin(mixin: &Mixin, path: &Path) -> Result<(), Error> {
+    let file_content = generate_rust_mixin(mixin);
+    let (mut file, filename) = get_object_file(mixin.get_name(), path)?;
+    match file.write_all(file_content.as_bytes()) {
+        Err(_e) => Err(Error::UnableToWriteToFile {
+            file: filename,
+            content: file_content,
+        }),
+        Ok(()) => Ok(()),
+    }
+}
+
+///Write the diesel schema describing the tables of all objects
+fn write_schema(objects: &ObjectDB, destination: PathBuf) -> Result<(), Error> {
+    let file_content = generate_schema(objects);
+    let (mut file, filename) = get_schema_file(destination)?;
+    match file.write_all(file_content.as_bytes()) {
+        Err(_e) => Err(Error::UnableToWriteToFile {
+            file: filename,
+            content: file_content,
+        }),
+        Ok(()) => Ok(()),
+    }
+}
+
 ///Generate the rust code to represent the object as a rust object whose data
 ///is linked to a SQLite database
 fn generate_rust<'a>(object: &Object, db: &ObjectDB, path: &'a Path) -> Result<(), Error> {
+    let mixins: Vec<&Mixin> = object.get_mixins().filter_map(|m| db.get_mixin(m)).collect();
     let file_content = format!(
-        "{}\n{}\n{}\n{}\n{}",
+        "{}\n{}\n{}\n{}\n{}{}",
         generate_rust_include(object),
         generate_rust_struct(object),
         generate_traits_impl(object, db),
         generate_rust_impl(object, db),
-        generate_rust_free_functions(object)
+        generate_rust_free_functions(object),
+        generate_rust_mixins_impl(object, &mixins)
     );
 
-    let (mut file, filename) = get_object_file(object, path)?;
+    let (mut file, filename) = get_object_file(object.get_name(), path)?;
 
     match file.write(file_content.as_bytes()) {
         Err(_e) => Err(Error::UnableToWriteToFile {
@@ -285,6 +484,8 @@ fn generate_rust_include(object: &Object) -> String {
     let mut refs = Vec::new();
     for at in object.get_attributes() {
         match at.get_reference() {
+            //the object itself is already defined in the module
+            Some(r) if r == object.get_name() => {}
             Some(r) => {
                 match refs.iter().find(|&x| x == &r) {
                     Some(_) => {}
@@ -306,6 +507,22 @@ fn generate_rust_include(object: &Object) -> String {
             );
     }
 
+    //and to the enumerations used as attribute type
+    let mut enums: Vec<&String> = Vec::new();
+    for at in object.get_attributes() {
+        if let Some(e) = at.get_enum() {
+            if !enums.contains(&e) {
+                enums.push(e);
+                extra_imports = extra_imports + &format!("\nuse super::{e};");
+            }
+        }
+    }
+
+    //and to the traits of the included mixins
+    for mixin in object.get_mixins() {
+        extra_imports = extra_imports + &format!("\nuse super::{mixin};");
+    }
+
     //Generate the list of imports to other object types, to the used library and to the schema
     format!(
         "use bdmg::{{SqlRepresentation,Object,ObjectIntrospection,ObjectIterator,ObjectFactory}};
@@ -323,12 +540,21 @@ use diesel::prelude::*;
 /// Generate the rust struct representing the object
 /// it supposes that the struct will be defined at the top level indentation
 fn generate_rust_struct(object: &Object) -> String {
+    //binary attributes are not shown by Debug, so that logs are not flooded with their content
+    let has_binary_attributes = object
+        .get_attributes()
+        .any(|at| at.get_type().get_base_type() == &BaseAttributeType::Bytes);
+    let debug_derive = if has_binary_attributes { "" } else { ", Debug" };
     //the derive macro for the struct. If it has relations, we must use the Associations derive of Diesel
     let derive_macros = if object.has_relations() {
         let mut belongs = String::new();
+        //diesel only allows one association per parent type
+        let mut parents: Vec<&String> = Vec::new();
         for at in object.get_attributes() {
             match at.get_reference() {
+                Some(r) if parents.contains(&r) => {}
                 Some(r) => {
+                    parents.push(r);
                     belongs = belongs
                         + &format!(
                             "#[diesel(belongs_to({}, foreign_key = {}))]\n",
@@ -339,9 +565,9 @@ fn generate_rust_struct(object: &Object) -> String {
                 None => {}
             }
         }
-        format!("#[derive(Queryable, Insertable, Serialize, Deserialize, Associations, Clone, Debug)]\n{belong}", belong = belongs)
+        format!("#[derive(Queryable, Insertable, Serialize, Deserialize, Associations, Clone{debug_derive})]\n{belong}", belong = belongs)
     } else {
-        String::from("#[derive(Queryable, Insertable, Serialize, Deserialize, Clone, Debug)]")
+        format!("#[derive(Queryable, Insertable, Serialize, Deserialize, Clone{debug_derive})]\n")
     };
 
     let mut atdef = String::new();
@@ -372,32 +598,50 @@ fn generate_rust_struct(object: &Object) -> String {
         None => String::new(),
     };
 
+    //the integer identifiers are given by the database, while the UUIDs are
+    //generated before the insertion and are therefore part of the inserted values
+    let (sql_id_type, insertable_id, insertable_id_conversion, id_deserialization) =
+        match object.get_id_type() {
+            IdentifierType::Integer => (
+                "Integer",
+                "",
+                "",
+                "deserializer.deserialize_i32(bdmg::ObjectIdVisitor {})? as i32",
+            ),
+            IdentifierType::Uuid => (
+                "Text",
+                "    id: bdmg::Uuid,\n",
+                "id: value.id,\n            ",
+                "<bdmg::Uuid as serde::Deserialize>::deserialize(deserializer)?",
+            ),
+        };
+
     let id_struct = format!(
         "/// The structure containing the id of {object_name}
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub struct Id{object_name} {{
-    pub(crate) id: i32
+    pub(crate) id: {id_type}
 }}
 
-///Structure like {object_name}, without the id.
+///Structure like {object_name}, without the id given by the database.
 /// Used internally when creating new {object_name} instances
 #[derive(Insertable, Serialize)]
 #[diesel(table_name = {table_name})]
 struct Insertable{object_name} {{
-    {attributes}
+{insertable_id}    {attributes}
     version: i64,
 }}
 
 impl From<{object_name}> for Insertable{object_name} {{
     fn from(value: {object_name}) -> Self {{
         Self {{
-            {attribute_conversion}version: value.version,
+            {insertable_id_conversion}{attribute_conversion}version: value.version,
         }}
     }}
 }}
 
-impl From<(i32, Insertable{object_name})> for {object_name} {{
-    fn from((new_id, value): (i32, Insertable{object_name})) -> Self {{
+impl From<({id_type}, Insertable{object_name})> for {object_name} {{
+    fn from((new_id, value): ({id_type}, Insertable{object_name})) -> Self {{
         Self {{
             id: new_id,
             {attribute_conversion}version: value.version,
@@ -405,16 +649,16 @@ impl From<(i32, Insertable{object_name})> for {object_name} {{
     }}
 }}
 
-impl<DB> diesel::deserialize::FromSql<diesel::sql_types::Integer, DB> for Id{object_name}
+impl<DB> diesel::deserialize::FromSql<diesel::sql_types::{sql_id_type}, DB> for Id{object_name}
 where
     DB: diesel::backend::Backend,
-    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, DB>,
+    {id_type}: diesel::deserialize::FromSql<diesel::sql_types::{sql_id_type}, DB>,
 {{
     fn from_sql(
         bytes: <DB as diesel::backend::Backend>::RawValue<'_>,
     ) -> diesel::deserialize::Result<Self> {{
         Ok(Self {{
-            id: i32::from_sql(bytes)?,
+            id: <{id_type}>::from_sql(bytes)?,
         }})
     }}
 }}
@@ -430,7 +674,7 @@ impl<'de> serde::de::Deserialize<'de> for Id{object_name} {{
     where
         D: serde::Deserializer<'de>,
     {{
-        let v = deserializer.deserialize_i32(bdmg::ObjectIdVisitor {{}})? as i32;
+        let v = {id_deserialization};
         Ok(Id{object_name} {{ id: v }})
     }}
 }}
@@ -438,10 +682,18 @@ impl<'de> serde::de::Deserialize<'de> for Id{object_name} {{
         object_name = object.get_name(),
         attributes = atdef,
         table_name = object.get_table_name(),
+        id_type = get_id_type(object.get_id_type()),
     );
 
+    let debug_impl = if has_binary_attributes {
+        generate_rust_debug_impl(object)
+    } else {
+        String::new()
+    };
+
     format!(
-        "/// {struct_comments}\n{derive}#[diesel(table_name = {table_name})]\npub struct {struct_name} {{\n    id: i32,\n{attributes}    version: i64\n}}\n\n{id_struct}\n\n",
+        "/// {struct_comments}\n{derive}#[diesel(table_name = {table_name})]\npub struct {struct_name} {{\n    id: {id_type},\n{attributes}    version: i64\n}}\n\n{debug_impl}{id_struct}\n\n",
+        id_type = get_id_type(object.get_id_type()),
         struct_comments = comment,
         derive = derive_macros,
         table_name = object.get_table_name(),
@@ -450,6 +702,41 @@ impl<'de> serde::de::Deserialize<'de> for Id{object_name} {{
     )
 }
 
+/// Generate the implementation of Debug for an object, where binary attributes
+/// are replaced by their size
+fn generate_rust_debug_impl(object: &Object) -> String {
+    let mut fields = String::new();
+    for at in object.get_attributes() {
+        let value = match at.get_type() {
+            AttributeType::Mandatory(BaseAttributeType::Bytes) => {
+                format!("&format_args!(\"<{{}} bytes>\", self.{}.len())", at.get_name())
+            }
+            AttributeType::Optional(BaseAttributeType::Bytes) => format!(
+                "&self.{}.as_ref().map(|v| format!(\"<{{}} bytes>\", v.len()))",
+                at.get_name()
+            ),
+            _ => format!("&self.{}", at.get_name()),
+        };
+        fields = format!(
+            "{fields}\n            .field(\"{attribute_name}\", {value})",
+            attribute_name = at.get_name()
+        );
+    }
+    format!(
+        "impl std::fmt::Debug for {object_name} {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        f.debug_struct(\"{object_name}\")
+            .field(\"id\", &self.id){fields}
+            .field(\"version\", &self.version)
+            .finish()
+    }}
+}}
+
+",
+        object_name = object.get_name()
+    )
+}
+
 fn generate_rust_select_clause(object: &Object, depth: usize) -> String {
     let white_space = "    ".repeat(depth);
     let end_space = "    ".repeat(depth - 1);
@@ -496,36 +783,105 @@ fn get_snake_name(name: &str) -> String {
     snake_name
 }
 
-///Retrieve the underlying rust type used for storage
-fn get_base_type(base: &BaseAttributeType) -> String {
-    match base {
+///Retrieve the rust type used to store the identifier of an object
+fn get_id_type(id_type: IdentifierType) -> String {
+    match id_type {
+        IdentifierType::Integer => String::from("i32"),
+        IdentifierType::Uuid => String::from("bdmg::Uuid"),
+    }
+}
+
+///Retrieve the underlying rust type used for storage, without the optional
+fn get_base_type(attribute: &Attribute) -> String {
+    match attribute.get_type().get_base_type() {
         BaseAttributeType::Integer => String::from("i64"),
+        BaseAttributeType::Float => String::from("f64"),
+        BaseAttributeType::Decimal { .. } => String::from("bdmg::Decimal"),
+        BaseAttributeType::Boolean => String::from("bool"),
         BaseAttributeType::String => String::from("String"),
-        BaseAttributeType::Reference(_) => String::from("i32"),
+        BaseAttributeType::Date => String::from("bdmg::chrono::NaiveDate"),
+        BaseAttributeType::Time => String::from("bdmg::chrono::NaiveTime"),
+        BaseAttributeType::Timestamp => String::from("bdmg::chrono::DateTime<bdmg::chrono::Utc>"),
+        BaseAttributeType::Duration => String::from("bdmg::Duration"),
+        BaseAttributeType::Bytes => String::from("Vec<u8>"),
+        BaseAttributeType::Uuid => String::from("bdmg::Uuid"),
+        BaseAttributeType::Json => String::from("bdmg::Json"),
+        BaseAttributeType::TypedJson(t) => format!("bdmg::Json<{t}>"),
+        BaseAttributeType::Enum(e) => e.clone(),
+        BaseAttributeType::Reference(_) => get_id_type(attribute.get_reference_id_type()),
     }
 }
 
+///Check if the rust type used for storage implements Copy
+fn is_copy_type(base: &BaseAttributeType) -> bool {
+    match base {
+        BaseAttributeType::Integer
+        | BaseAttributeType::Float
+        | BaseAttributeType::Decimal { .. }
+        | BaseAttributeType::Boolean
+        | BaseAttributeType::Date
+        | BaseAttributeType::Time
+        | BaseAttributeType::Timestamp
+        | BaseAttributeType::Duration
+        | BaseAttributeType::Uuid
+        | BaseAttributeType::Enum(_)
+        | BaseAttributeType::Reference(_) => true,
+        BaseAttributeType::String
+        | BaseAttributeType::Bytes
+        | BaseAttributeType::Json
+        | BaseAttributeType::TypedJson(_) => false,
+    }
+}
+
+///Retrieve the expression parsing the string representation contained in `value`.
+/// The resulting expression is a Result containing the storage type, whose error
+/// implements std::error::Error
+fn get_parse_expression(attribute: &Attribute, value: &str) -> String {
+    match attribute.get_type() {
+        AttributeType::Mandatory(BaseAttributeType::Bytes) => {
+            format!("{value}.parse::<bdmg::Base64>().map(Vec::from)")
+        }
+        AttributeType::Optional(BaseAttributeType::Bytes) => {
+            format!("bdmg::extract_optional::<bdmg::Base64>({value}).map(|v| v.map(Vec::from))")
+        }
+        AttributeType::Mandatory(_) => format!("{value}.parse::<{}>()", get_base_type(attribute)),
+        AttributeType::Optional(_) => {
+            format!("bdmg::extract_optional::<{}>({value})", get_base_type(attribute))
+        }
+    }
+}
+
+///Retrieve the expression computing the default value of the attribute, if any.
+/// The expression returns a bdmg::Error if the default value can not be parsed
+fn get_default_expression(attribute: &Attribute) -> Option<String> {
+    attribute.get_default().map(|default| {
+        format!(
+            "{}.map_err(|e| bdmg::Error::ParsingError(Box::new(e)))?",
+            get_parse_expression(attribute, &format!("{default:?}"))
+        )
+    })
+}
+
 ///Retrieve the underlying rust type used for storage
-fn get_attribute_type(at_type: &AttributeType) -> String {
-    match at_type {
-        AttributeType::Mandatory(base) => get_base_type(base),
-        AttributeType::Optional(base) => format!("Option<{}>", get_base_type(base)),
+fn get_attribute_type(attribute: &Attribute) -> String {
+    match attribute.get_type() {
+        AttributeType::Mandatory(_) => get_base_type(attribute),
+        AttributeType::Optional(_) => format!("Option<{}>", get_base_type(attribute)),
     }
 }
 
 ///Retrieve the expected type in generic rust code, independent
 /// of the underlying storage type
-fn get_attribute_type_param_type(at_type: &AttributeType) -> String {
+fn get_attribute_type_param_type(attribute: &Attribute) -> String {
     //Retrieve the base attribute type and if it is optional
-    let (opt, base) = match at_type {
+    let (opt, base) = match attribute.get_type() {
         AttributeType::Mandatory(base) => (false, base),
         AttributeType::Optional(base) => (true, base),
     };
     //create the rust representation of the base type
     let base_type = match base {
-        BaseAttributeType::Integer => String::from("i64"),
-        BaseAttributeType::String => String::from("String"),
         BaseAttributeType::Reference(r) => format!("&{}", r),
+        _other => get_base_type(attribute),
     };
 
     if opt {
@@ -537,13 +893,13 @@ fn get_attribute_type_param_type(at_type: &AttributeType) -> String {
 
 /// Retrieve the underlying rust type used for storage
 fn get_rust_type(attribute: &Attribute) -> String {
-    get_attribute_type(attribute.get_type())
+    get_attribute_type(attribute)
 }
 
 /// Retrieve the type that should be used, regardless of the
 /// underlying typed used for storage
 fn get_rust_param_type(attribute: &Attribute) -> String {
-    get_attribute_type_param_type(attribute.get_type())
+    get_attribute_type_param_type(attribute)
 }
 
 /// Retrieve the type that can be passed as function as a borrowed parameter
@@ -559,9 +915,11 @@ fn get_rust_borrowed_type(attribute: &Attribute) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::Attribute;
+    use crate::sqlite_generator::write_migration;
+    use crate::{Attribute, AttributeConstraints, AttributeType, BaseAttributeType, ObjectDB};
 
-    use super::{get_rust_borrowed_type, get_rust_type};
+    use super::rust_impl::generate_rust_impl;
+    use super::{generate_rust_migrations, get_rust_borrowed_type, get_rust_string_literal, get_rust_type};
 
     #[test]
     fn borrowed_type() {
@@ -605,6 +963,15 @@ mod tests {
         )
         .unwrap();
         assert_eq!(String::from("i64"), get_rust_type(&at));
+        let at: Attribute =
+            serde_json::from_slice(b"{\"name\": \"duration\",\"is\": {\"Mandatory\": \"Float\"}}")
+                .unwrap();
+        assert_eq!(String::from("f64"), get_rust_type(&at));
+        let at: Attribute = serde_json::from_slice(
+            b"{\"name\": \"price\",\"is\": {\"Mandatory\": {\"Decimal\": {\"precision\": 10, \"scale\": 2}}}}",
+        )
+        .unwrap();
+        assert_eq!(String::from("bdmg::Decimal"), get_rust_type(&at));
         let at: Attribute =
             serde_json::from_slice(b"{\"name\": \"duration\",\"is\": {\"Mandatory\": \"String\"}}")
                 .unwrap();
@@ -620,6 +987,17 @@ mod tests {
             serde_json::from_slice(b"{\"name\": \"duration\",\"is\": {\"Optional\": \"Integer\"}}")
                 .unwrap();
         assert_eq!(String::from("Option<i64>"), get_rust_type(&at));
+        let at: Attribute =
+            serde_json::from_slice(b"{\"name\": \"duration\",\"is\": {\"Optional\": \"Float\"}}")
+                .unwrap();
+        assert_eq!(String::from("Option<f64>"), get_rust_type(&at));
+        let at: Attribute =
+            serde_json::from_slice(b"{\"name\": \"duration\",\"is\": {\"Optional\": \"Timestamp\"}}")
+                .unwrap();
+        assert_eq!(
+            String::from("Option<bdmg::chrono::DateTime<bdmg::chrono::Utc>>"),
+            get_rust_type(&at)
+        );
         let at: Attribute =
             serde_json::from_slice(b"{\"name\": \"duration\",\"is\": {\"Optional\": \"String\"}}")
                 .unwrap();
@@ -630,4 +1008,132 @@ mod tests {
         .unwrap();
         assert_eq!(String::from("Option<i32>"), get_rust_type(&at));
     }
+
+    #[test]
+    fn migrations() {
+        assert_eq!("r#\"a \"b\"\"#", get_rust_string_literal("a \"b\""));
+        assert_eq!("r##\"\"#\"##", get_rust_string_literal("\"#"));
+
+        let directory = std::env::temp_dir().join(format!("bdmg2k_migrations_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let directory = directory.display().to_string();
+        let empty = ObjectDB::builder().build().unwrap();
+        let first = ObjectDB::builder().object("Author").build().unwrap();
+        let second = ObjectDB::builder()
+            .migrations(&directory)
+            .object("Author")
+            .attr(Attribute::new("name", AttributeType::Optional(BaseAttributeType::String)))
+            .build()
+            .unwrap();
+        write_migration(&first, &second, &directory, "0002_author_name").unwrap();
+        write_migration(&empty, &first, &directory, "0001_install").unwrap();
+
+        let code = generate_rust_migrations(&second).unwrap();
+        let install = code.find("\"0001_install\"").unwrap();
+        let author_name = code.find("\"0002_author_name\"").unwrap();
+        assert!(install < author_name);
+        assert!(code.contains("ALTER TABLE Author ADD COLUMN name VARCHAR;"));
+        assert!(code.contains("pub const MIGRATIONS: &[bdmg::Migration] = &["));
+        assert!(generate_rust_migrations(&first).unwrap().is_empty());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn hierarchy_functions() {
+        let path = std::env::temp_dir().join(format!("bdmg2k_hierarchy_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            b"{\"rust_destination\": \"\", \"objects\": [{\"tbnm\": \"categories\", \"name\": \"Category\", \"attr\": [
+                {\"name\": \"label\", \"is\": {\"Mandatory\": \"String\"}},
+                {\"name\": \"parent\", \"is\": {\"Optional\": {\"Reference\": \"Category\"}}}
+            ]}]}",
+        )
+        .unwrap();
+        let db = ObjectDB::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let code = generate_rust_impl(db.get_object("Category").unwrap(), &db);
+        //the children are only retrieved through the hierarchy
+        assert!(code.contains("pub fn get_children("));
+        assert!(!code.contains("pub fn get_categorys("));
+    }
+
+    #[test]
+    fn optional_indexed_loaders() {
+        let db = ObjectDB::builder()
+            .object("Owner")
+            .table("owners")
+            .attr(Attribute::new("nick", AttributeType::Optional(BaseAttributeType::String)).indexed())
+            .object("Pet")
+            .table("pets")
+            .attr(
+                Attribute::new(
+                    "owner",
+                    AttributeType::Optional(BaseAttributeType::Reference(String::from("Owner"))),
+                )
+                .indexed(),
+            )
+            .build()
+            .unwrap();
+        //the instances without value are retrieved with IS NULL
+        let code = generate_rust_impl(db.get_object("Owner").unwrap(), &db);
+        assert!(code.contains("pub fn load_all_by_nick(connection: &mut SqliteConnection, attribute: &Option<String>)"));
+        assert!(code.contains("let query = match attribute.as_deref() {"));
+        assert!(code.contains("Some(value) => query.filter(owners::nick.eq(value)),"));
+        assert!(code.contains("None => query.filter(owners::nick.is_null()),"));
+        let code = generate_rust_impl(db.get_object("Pet").unwrap(), &db);
+        assert!(code.contains("pub fn load_all_by_owner(connection: &mut SqliteConnection, attribute: &Option<&Owner>)"));
+        assert!(code.contains("let query = match attribute.map(|a| a.id().id) {"));
+        assert!(code.contains("None => query.filter(pets::owner.is_null()),"));
+    }
+
+    #[test]
+    fn optional_unique_group() {
+        let db = ObjectDB::builder()
+            .object("Owner")
+            .table("owners")
+            .object("Pet")
+            .table("pets")
+            .attr(Attribute::new(
+                "owner",
+                AttributeType::Optional(BaseAttributeType::Reference(String::from("Owner"))),
+            ))
+            .attr(Attribute::new("name", AttributeType::Mandatory(BaseAttributeType::String)))
+            .attr(Attribute::new("kind", AttributeType::Mandatory(BaseAttributeType::Integer)))
+            .unique(&["owner", "name"])
+            .unique(&["name", "kind"])
+            .build()
+            .unwrap();
+        let code = generate_rust_impl(db.get_object("Pet").unwrap(), &db);
+        assert!(code.contains(
+            "pub fn load_from_owner_and_name(connection: &mut SqliteConnection, a_owner: &Option<&Owner>, a_name: &str)"
+        ));
+        assert!(code.contains("let query = match a_owner.map(|a| a.id().id) {"));
+        assert!(code.contains("None => query.filter(pets::owner.is_null()),"));
+        assert!(code.contains("let query = query.filter(pets::name.eq(&a_name));"));
+        //the groups without optional attributes are loaded as before
+        assert!(code.contains(".filter(pets::name.eq(&a_name))\n            .filter(pets::kind.eq(&a_kind))"));
+    }
+
+    #[test]
+    fn constraint_literals() {
+        let db = ObjectDB::builder()
+            .object("Measure")
+            .table("measures")
+            .attr(
+                Attribute::new("ratio", AttributeType::Mandatory(BaseAttributeType::Float))
+                    .constraints(AttributeConstraints::default().min(".5").max("1.")),
+            )
+            .attr(
+                Attribute::new("level", AttributeType::Mandatory(BaseAttributeType::Integer))
+                    .constraints(AttributeConstraints::default().max("+5").one_of(&["+1", "-2"])),
+            )
+            .build()
+            .unwrap();
+        //the bounds are written as valid rust literals
+        let code = generate_rust_impl(db.get_object("Measure").unwrap(), &db);
+        assert!(code.contains("*v < 0.5_f64"));
+        assert!(code.contains("*v > 1.0_f64"));
+        assert!(code.contains("*v > 5"));
+        assert!(code.contains("![1, -2].contains(v)"));
+    }
 }
@@ -17,14 +17,18 @@
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
 
+use crate::model_error::{Location, ModelError, ModelErrorKind};
 use crate::object::Object;
 use crate::objectdb::ObjectDB;
-use crate::{Attribute, AttributeType, BaseAttributeType};
+use crate::{Attribute, AttributeType, BaseAttributeType, IdentifierType};
+
+use std::collections::HashMap;
 
 pub fn generate_rust_impl(object: &Object, db: &ObjectDB) -> String {
     format!(
-        "impl {} {{\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n}}",
+        "impl {} {{\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n}}",
         object.get_name(),
+        validatorsfn(object),
         newfn(object),
         createfn(object),
         mass_create(object),
@@ -36,9 +40,124 @@ pub fn generate_rust_impl(object: &Object, db: &ObjectDB) -> String {
         gettersfn(object),
         settersfn(object),
         get_relations(object, db),
+        hierarchyfn(object),
     )
 }
 
+/// Check that the functions generated for the object have different names, e.g. that
+/// the getter of an attribute `tags` does not collide with the function retrieving
+/// the `Tag` referencing the object
+pub(crate) fn validate_function_names(object: &Object, db: &ObjectDB) -> Vec<ModelError> {
+    let object_name = object.get_name();
+    //the name of each function, with the element of the model it is generated for
+    let mut functions: Vec<(String, String, Location)> = Vec::new();
+    let mut fixed = vec![
+        "id", "new", "create", "mass_create", "delete", "load", "load_from_id", "load_all", "load_multiple",
+        "get_nb_defined",
+    ];
+    if object.is_object_immutable_relation().is_some() {
+        fixed.push("load_from_content");
+    }
+    for function_name in fixed {
+        functions.push((function_name.to_string(), String::from("the object"), object.get_location().clone()));
+    }
+    let mut attribute_names = Vec::new();
+    for at in object.get_attributes() {
+        let name = at.get_name();
+        //the attributes defined twice are already reported by the object
+        if attribute_names.contains(&name) {
+            continue;
+        }
+        attribute_names.push(name);
+        let mut names = vec![format!("get_{name}")];
+        if at.get_reference().is_some() {
+            names.push(format!("get_{name}_id"));
+        }
+        if at.get_type().get_base_type() == &BaseAttributeType::Json {
+            names.push(format!("get_{name}_as"));
+        }
+        if at.is_mutable() {
+            names.push(format!("set_{name}"));
+        }
+        if !invalid_value_conditions(at).is_empty() {
+            names.push(format!("validate_{name}"));
+        }
+        if at.is_unique() {
+            names.push(format!("load_from_{name}"));
+        }
+        if at.is_indexed() {
+            names.push(format!("load_all_by_{name}"));
+        }
+        if is_json(at) {
+            names.push(format!("load_all_by_{name}_path"));
+        }
+        for function_name in names {
+            functions.push((function_name, format!("the attribute '{name}'"), at.get_location().clone()));
+        }
+    }
+    for (index, group) in object.get_unique_groups().enumerate() {
+        functions.push((
+            format!("load_from_{}", group.join("_and_")),
+            format!("the unique group ({})", group.join(", ")),
+            object.get_location().item("unique", index),
+        ));
+    }
+    for referencing_name in object.get_referencing_objects() {
+        let referencing = match db.get_object(referencing_name) {
+            Some(obj) => obj,
+            None => continue,
+        };
+        let attributes = referencing.get_relation_attributes(object.get_name());
+        for attribute in &attributes {
+            if is_hierarchy_reference(object, referencing_name, attribute) {
+                continue;
+            }
+            functions.push((
+                referencing_function_name(referencing_name, attribute, attributes.len() > 1),
+                format!("the reference '{referencing_name}.{}'", attribute.get_name()),
+                attribute.get_location().clone(),
+            ));
+        }
+        let sides = referencing.get_relation_sides(object.get_name());
+        for (attribute, other_attribute) in &sides {
+            if let Some(destination_name) = other_attribute.get_reference() {
+                functions.push((
+                    related_function_name(destination_name, referencing_name, attribute, sides.len() > 1),
+                    format!("the relation '{referencing_name}.{}'", attribute.get_name()),
+                    attribute.get_location().clone(),
+                ));
+            }
+        }
+    }
+    if let Some(parent) = object.get_hierarchy_attribute() {
+        for function_name in ["get_children", "get_ancestors", "get_descendants", "get_root", "get_depth"] {
+            functions.push((
+                function_name.to_string(),
+                format!("the hierarchy given by '{}'", parent.get_name()),
+                parent.get_location().clone(),
+            ));
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut origins: HashMap<&String, &String> = HashMap::new();
+    for (function_name, origin, location) in &functions {
+        match origins.get(function_name) {
+            Some(first_origin) => errors.push(
+                ModelError::new(
+                    ModelErrorKind::DuplicateName,
+                    format!("The function '{function_name}' of '{object_name}' is generated both for {first_origin} and for {origin}"),
+                )
+                .at(location),
+            ),
+            None => {
+                origins.insert(function_name, origin);
+            }
+        }
+    }
+    errors
+}
+
 fn loadmultiplefn(object: &Object) -> String {
     format!(
         "
@@ -91,10 +210,41 @@ fn gettersfn(object: &Object) -> String {
     );
     for at in object.get_attributes() {
         getters = getters + &attribute_getter(at);
+        if at.get_type().get_base_type() == &BaseAttributeType::Json {
+            getters = getters + &json_typed_getter(at);
+        }
     }
     getters
 }
 
+/// Check if the attribute is a JSON document, typed or not
+fn is_json(attribute: &Attribute) -> bool {
+    matches!(
+        attribute.get_type().get_base_type(),
+        BaseAttributeType::Json | BaseAttributeType::TypedJson(_)
+    )
+}
+
+/// Generate the getter converting an untyped JSON document into a rust type
+fn json_typed_getter(attribute: &Attribute) -> String {
+    let (result_type, body) = match attribute.get_type() {
+        AttributeType::Mandatory(_) => ("T", "self.{name}.to_typed()"),
+        AttributeType::Optional(_) => (
+            "Option<T>",
+            "match &self.{name} {\n            Some(v) => v.to_typed().map(Some),\n            None => Ok(None)\n        }",
+        ),
+    };
+    format!(
+        "
+    /// Retrieve the value of the {name} attribute, converted into the given type
+    pub fn get_{name}_as<T: serde::de::DeserializeOwned>(&self) -> Result<{result_type}, bdmg::serde_json::Error> {{
+        {body}
+    }}",
+        name = attribute.get_name(),
+        body = body.replace("{name}", attribute.get_name()),
+    )
+}
+
 fn attribute_getter(attribute: &Attribute) -> String {
     let comment = format!(
         "/// Retrieve the value of the {attribute_name} attribute.{desc}\n    ",
@@ -129,7 +279,7 @@ fn attribute_getter(attribute: &Attribute) -> String {
             }
         },
         None => {
-            if attribute.get_type().get_base_type() == &BaseAttributeType::Integer {
+            if super::is_copy_type(attribute.get_type().get_base_type()) {
                 format!(
                     "\n    {comment}pub fn get_{}(&self) -> {} {{ self.{} }}",
                     attribute.get_name(),
@@ -175,6 +325,30 @@ fn atribute_setter<'a>(object: &Object, attribute: &Attribute, table_name: &'a S
         );
 
     let check_if_needed = match attribute.get_reference() {
+        //floats are compared on their bit representation, so that setting NaN twice is not an update
+        None if attribute.get_type().get_base_type() == &BaseAttributeType::Float => {
+            match attribute.get_type() {
+                AttributeType::Mandatory(_) => format!(
+                    "\n        if attr_value.to_bits() == self.{name}.to_bits() {{ return Ok(());}}\n",
+                    name = attribute.get_name()
+                ),
+                AttributeType::Optional(_) => format!(
+                    "\n        if attr_value.map(f64::to_bits) == self.{name}.map(f64::to_bits) {{ return Ok(());}}\n",
+                    name = attribute.get_name()
+                ),
+            }
+        }
+        //JSON documents are compared on their representation, as the type may not implement PartialEq
+        None if is_json(attribute) => match attribute.get_type() {
+            AttributeType::Mandatory(_) => format!(
+                "\n        if attr_value.to_string() == self.{name}.to_string() {{ return Ok(());}}\n",
+                name = attribute.get_name()
+            ),
+            AttributeType::Optional(_) => format!(
+                "\n        if attr_value.as_ref().map(ToString::to_string) == self.{name}.as_ref().map(ToString::to_string) {{ return Ok(());}}\n",
+                name = attribute.get_name()
+            ),
+        },
         None => format!(
             "\n        if attr_value == self.{name} {{ return Ok(());}}\n",
             name = attribute.get_name()
@@ -182,12 +356,12 @@ fn atribute_setter<'a>(object: &Object, attribute: &Attribute, table_name: &'a S
         Some(_) => match attribute.get_type() {
             AttributeType::Mandatory(_) => {
                 format!(
-                    "\n        if attr_value.get_id() == self.{name} {{ return Ok(());}}\n",
+                    "\n        if attr_value.id().id == self.{name} {{ return Ok(());}}\n",
                     name = attribute.get_name()
                 )
             }
             AttributeType::Optional(_) => {
-                format!("\n        if (attr_value.is_none() && self.{name}.is_none()) || (attr_value.is_some() && Some(attr_value.unwrap().get_id()) == self.{name}) {{ return Ok(()); }} ",
+                format!("\n        if (attr_value.is_none() && self.{name}.is_none()) || (attr_value.is_some() && Some(attr_value.unwrap().id().id) == self.{name}) {{ return Ok(()); }} ",
                         name = attribute.get_name())
             }
         },
@@ -216,9 +390,9 @@ fn atribute_setter<'a>(object: &Object, attribute: &Attribute, table_name: &'a S
         value = {
             match attribute.get_reference() {
                 Some(_) => match attribute.get_type() {
-                    AttributeType::Mandatory(_) => "attr_value.get_id()",
+                    AttributeType::Mandatory(_) => "attr_value.id().id",
                     AttributeType::Optional(_) => {
-                        "match attr_value { Some(val) => Some(val.get_id()), None => None } "
+                        "match attr_value { Some(val) => Some(val.id().id), None => None } "
                     }
                 },
                 None => "&attr_value",
@@ -245,9 +419,9 @@ fn atribute_setter<'a>(object: &Object, attribute: &Attribute, table_name: &'a S
         attr_value = {
             match attribute.get_reference() {
                 Some(_) => match attribute.get_type() {
-                    AttributeType::Mandatory(_) => "attr_value.get_id()",
+                    AttributeType::Mandatory(_) => "attr_value.id().id",
                     AttributeType::Optional(_) => {
-                        "match attr_value { Some(val) => Some(val.get_id()), None => None } "
+                        "match attr_value { Some(val) => Some(val.id().id), None => None } "
                     }
                 },
                 None => "attr_value",
@@ -255,7 +429,171 @@ fn atribute_setter<'a>(object: &Object, attribute: &Attribute, table_name: &'a S
         }
     );
 
-    declaration + &check_if_needed + &validator + &update_query + &result_treatment
+    declaration
+        + &check_if_needed
+        + &cycle_check(object, attribute)
+        + &value_check(attribute, "attr_value")
+        + &validator
+        + &update_query
+        + &result_treatment
+}
+
+/// Generate the check refusing a parent that would create a cycle in the hierarchy,
+/// if the attribute is the one giving the parent of an instance
+fn cycle_check(object: &Object, attribute: &Attribute) -> String {
+    match object.get_hierarchy_attribute() {
+        Some(parent) if parent.get_name() == attribute.get_name() => format!(
+            "
+        if let Some(parent) = attr_value {{
+            if parent.id == self.id || parent.get_ancestors(connection)?.iter().any(|ancestor| ancestor.id == self.id) {{
+                return Err(bdmg::Error::InvalidAttributeValue(String::from(\"'{attribute_name}' can not reference the instance or one of its descendants\")));
+            }}
+        }}\n",
+            attribute_name = attribute.get_name()
+        ),
+        _ => String::new(),
+    }
+}
+
+/// Generate the check refusing a value that does not respect the constraints of the
+/// attribute, if any
+fn value_check(attribute: &Attribute, value: &str) -> String {
+    if invalid_value_conditions(attribute).is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n        Self::validate_{attribute_name}(&{value})?;\n",
+        attribute_name = attribute.get_name()
+    )
+}
+
+/// Retrieve the conditions, on the borrowed value `v`, under which a value can not be
+/// stored in the attribute, along with the message explaining why: a binary value
+/// exceeding the maximum size, a decimal exceeding the precision or a value not
+/// respecting the declared constraints
+fn invalid_value_conditions(attribute: &Attribute) -> Vec<(String, String)> {
+    let base_type = attribute.get_type().get_base_type();
+    let constraints = attribute.get_constraints();
+    let mut conditions = Vec::new();
+    if let BaseAttributeType::Decimal { precision, scale } = base_type {
+        conditions.push((
+            format!("!v.fits({precision}, {scale})"),
+            format!("does not fit in a decimal({precision}, {scale})"),
+        ));
+    }
+    if let Some(max_size) = attribute.get_max_size() {
+        conditions.push((
+            format!("v.len() > {max_size}"),
+            format!("exceeds the maximum size of {max_size} bytes"),
+        ));
+    }
+    //the bounds are given with the string representation of the attribute, the numbers
+    //are written back as rust literals (e.g. `+5` or `.5` are not)
+    let bound = |b: &String, operator: &str| match base_type {
+        BaseAttributeType::Integer => format!("*v {operator} {}", integer_literal(b)),
+        BaseAttributeType::Float => match b.parse::<f64>() {
+            Ok(parsed) => format!("*v {operator} {parsed:?}_f64"),
+            Err(_e) => format!("*v {operator} {b}_f64"),
+        },
+        _ => format!(
+            "{b:?}.parse::<{}>().is_ok_and(|bound| *v {operator} bound)",
+            super::get_base_type(attribute)
+        ),
+    };
+    if let Some(min) = constraints.get_min() {
+        conditions.push((bound(min, "<"), format!("must be at least {min}")));
+    }
+    if let Some(max) = constraints.get_max() {
+        conditions.push((bound(max, ">"), format!("must be at most {max}")));
+    }
+    if let Some(min_length) = constraints.get_min_length() {
+        conditions.push((
+            format!("v.chars().count() < {min_length}"),
+            format!("must contain at least {min_length} characters"),
+        ));
+    }
+    if let Some(max_length) = constraints.get_max_length() {
+        conditions.push((
+            format!("v.chars().count() > {max_length}"),
+            format!("must contain at most {max_length} characters"),
+        ));
+    }
+    if let Some(pattern) = constraints.get_pattern() {
+        conditions.push((
+            String::from("!PATTERN.get_or_init(|| bdmg::regex::Regex::new(PATTERN_SOURCE).expect(\"invalid pattern\")).is_match(v)"),
+            format!("must match the pattern '{pattern}'"),
+        ));
+    }
+    if let Some(values) = constraints.get_one_of() {
+        let condition = match base_type {
+            BaseAttributeType::Integer => {
+                let values: Vec<String> = values.iter().map(|value| integer_literal(value)).collect();
+                format!("![{}].contains(v)", values.join(", "))
+            }
+            _ => {
+                let values: Vec<String> = values.iter().map(|v| format!("{v:?}")).collect();
+                if constraints.is_case_insensitive() {
+                    format!("![{}].iter().any(|a| a.eq_ignore_ascii_case(v))", values.join(", "))
+                } else {
+                    format!("![{}].contains(&v.as_str())", values.join(", "))
+                }
+            }
+        };
+        conditions.push((condition, format!("must be one of {}", values.join(", "))));
+    }
+    conditions
+}
+
+/// Write an integer given in the model as a rust literal
+fn integer_literal(value: &str) -> String {
+    match value.parse::<i64>() {
+        Ok(parsed) => format!("{parsed:?}"),
+        Err(_e) => value.to_string(),
+    }
+}
+
+/// Generate the functions checking that values respect the constraints of the attributes
+fn validatorsfn(object: &Object) -> String {
+    let mut validators = String::new();
+    for at in object.get_attributes() {
+        let conditions = invalid_value_conditions(at);
+        if conditions.is_empty() {
+            continue;
+        }
+        let mut checks = String::new();
+        for (condition, message) in conditions {
+            checks += &format!(
+                "\n        if {condition} {{ return Err(bdmg::Error::InvalidAttributeValue(String::from({message:?}))); }}",
+                message = format!("'{}' {message}", at.get_name())
+            );
+        }
+        //the pattern is compiled once, and must match the whole value
+        let pattern = match at.get_constraints().get_pattern() {
+            Some(p) => format!(
+                "\n        static PATTERN: std::sync::OnceLock<bdmg::regex::Regex> = std::sync::OnceLock::new();\n        const PATTERN_SOURCE: &str = {anchored:?};",
+                anchored = format!("^(?:{p})$")
+            ),
+            None => String::new(),
+        };
+        let body = match at.get_type() {
+            AttributeType::Mandatory(_) => format!("\n        let v = value;{checks}"),
+            AttributeType::Optional(_) => format!(
+                "\n        if let Some(v) = value {{{checks}\n        }}",
+                checks = checks.replace("\n", "\n    ")
+            ),
+        };
+        validators += &format!(
+            "
+    /// Check that the value can be stored in the attribute {attribute_name}
+    pub fn validate_{attribute_name}(value: &{rust_type}) -> Result<(), bdmg::Error> {{{pattern}{body}
+        Ok(())
+    }}
+",
+            attribute_name = at.get_name(),
+            rust_type = super::get_rust_type(at),
+        );
+    }
+    validators
 }
 
 fn nbdefinedfn(object: &Object) -> String {
@@ -278,29 +616,28 @@ fn loadfn(object: &Object) -> String {
         Some((first, second)) => {
             let first_type = first.get_reference().unwrap();
             let first_name = first.get_name();
-            let first_arg_name = first_type.to_ascii_lowercase();
             let second_type = second.get_reference().unwrap();
             let second_name = second.get_name();
-            let second_arg_name = second_type.to_ascii_lowercase();
             let table_name = object.get_table_name();
 
+            //the arguments are named after the attributes, as both may reference the same type
             loaders = loaders + &format!("
         /// Load an instance based on the content of this object
-    pub fn load_from_content(connection: &mut SqliteConnection, a_{first_arg_name}: Id{first_type}, a_{second_arg_name}: Id{second_type}) -> Result<Vec<{object_name}>, bdmg::Error> {{
+    pub fn load_from_content(connection: &mut SqliteConnection, a_{first_name}: Id{first_type}, a_{second_name}: Id{second_type}) -> Result<Vec<{object_name}>, bdmg::Error> {{
         return Ok({table_name}::dsl::{table_name}
-            .filter(super::schema::{table_name}::{first_name}.eq(a_{first_arg_name}.id))
-            .filter(super::schema::{table_name}::{second_name}.eq(a_{second_arg_name}.id))
+            .filter(super::schema::{table_name}::{first_name}.eq(a_{first_name}.id))
+            .filter(super::schema::{table_name}::{second_name}.eq(a_{second_name}.id))
             .load::<{object_name}>(connection)?);
     }}",object_name = object.get_name(),)
         }
         None => {}
     }
 
-    //Create the loaders based on attributes that are indexable references
+    //Create the loaders based on attributes that are unique
     for at in object.get_attributes() {
-        if at.is_indexable() {
+        if at.is_unique() {
             let value = match at.get_reference() {
-                Some(_referenced_name) => String::from("attribute.get_id()"),
+                Some(_referenced_name) => String::from("attribute.id().id"),
                 None => String::from("&attribute"),
             };
             loaders = loaders
@@ -325,10 +662,117 @@ fn loadfn(object: &Object) -> String {
                         );
         }
     }
+    //Create the loaders based on attributes that are indexed without being unique
+    for at in object.get_attributes().filter(|at| at.is_indexed() && !at.is_optional()) {
+        let value = match at.get_reference() {
+            Some(_referenced_name) => String::from("attribute.id().id"),
+            None => String::from("&attribute"),
+        };
+        loaders = loaders
+            + &format!(
+                "
+    /// Load all the instances having the given value for the attribute {attribute_name}
+    pub fn load_all_by_{attribute_name}(connection: &mut SqliteConnection, attribute: {borrowed_type}) -> Result<Vec<{object_name}>, bdmg::Error> {{
+        Ok({table_name}::dsl::{table_name}
+            {select_clause}
+            .filter({table_name}::{attribute_name}.eq({attribute_value}))
+            .order({table_name}::id.asc())
+            .load::<{object_name}>(connection)?)
+    }}",
+                attribute_name = at.get_name(),
+                attribute_value = value,
+                borrowed_type = super::get_rust_borrowed_type(at),
+                object_name = object.get_name(),
+                table_name = object.get_table_name(),
+                select_clause = super::generate_rust_select_clause(object, 3),
+            );
+    }
+    //The optional attributes may be looked up without value, the query is built in two steps
+    for at in object.get_attributes().filter(|at| at.is_indexed() && at.is_optional()) {
+        loaders = loaders
+            + &format!(
+                "
+    /// Load all the instances having the given value for the attribute {attribute_name},
+    /// or all the instances without value for it when None is given
+    pub fn load_all_by_{attribute_name}(connection: &mut SqliteConnection, attribute: {borrowed_type}) -> Result<Vec<{object_name}>, bdmg::Error> {{
+        let query = {table_name}::dsl::{table_name}
+            {select_clause}
+            .order({table_name}::id.asc())
+            .into_boxed();{filter}
+        Ok(query.load::<{object_name}>(connection)?)
+    }}",
+                attribute_name = at.get_name(),
+                borrowed_type = super::get_rust_borrowed_type(at),
+                object_name = object.get_name(),
+                table_name = object.get_table_name(),
+                select_clause = super::generate_rust_select_clause(object, 3),
+                filter = optional_filter(object, at, "attribute"),
+            );
+    }
+    //Create the loaders based on the groups of attributes that are unique
+    for group in object.get_unique_groups() {
+        let attributes = object.get_group_attributes(group);
+        if attributes.iter().any(|at| at.is_optional()) {
+            loaders = loaders + &optional_group_loader(object, group, &attributes);
+            continue;
+        }
+        let mut params = String::new();
+        let mut filters = String::new();
+        for at in &attributes {
+            let value = match at.get_reference() {
+                Some(_referenced_name) => format!("a_{}.id().id", at.get_name()),
+                None => format!("&a_{}", at.get_name()),
+            };
+            params += &format!(", a_{}: {}", at.get_name(), super::get_rust_borrowed_type(at));
+            filters += &format!(
+                "\n            .filter({table_name}::{attribute_name}.eq({value}))",
+                attribute_name = at.get_name(),
+                table_name = object.get_table_name(),
+            );
+        }
+        loaders = loaders
+            + &format!(
+                "
+    /// Load an instance based on the attributes {attribute_names}
+    pub fn load_from_{function_suffix}(connection: &mut SqliteConnection{params}) -> Result<{object_name}, bdmg::Error> {{
+        let mut result = {table_name}::dsl::{table_name}{filters}
+            .limit(1)
+            .load::<{object_name}>(connection)?;
+        match result.len() {{
+            1 => Ok(result.pop().unwrap()),
+            _ => Err(bdmg::Error::ElementNotFound)
+        }}
+    }}",
+                attribute_names = group.join(", "),
+                function_suffix = group.join("_and_"),
+                object_name = object.get_name(),
+                table_name = object.get_table_name(),
+            );
+    }
+    //Create the loaders filtering on the content of the JSON documents
+    for at in object.get_attributes().filter(|at| is_json(at)) {
+        loaders = loaders
+            + &format!(
+                "
+    /// Load all the instances whose attribute {attribute_name} contains the given value
+    /// at the given JSON path (e.g. `$.settings.theme`)
+    pub fn load_all_by_{attribute_name}_path(connection: &mut SqliteConnection, path: &str, value: &bdmg::serde_json::Value) -> Result<Vec<{object_name}>, bdmg::Error> {{
+        Ok({table_name}::dsl::{table_name}
+            {select_clause}
+            .filter(bdmg::json_path_condition(\"{attribute_name}\", path, value))
+            .order({table_name}::id.asc())
+            .load::<{object_name}>(connection)?)
+    }}",
+                attribute_name = at.get_name(),
+                object_name = object.get_name(),
+                table_name = object.get_table_name(),
+                select_clause = super::generate_rust_select_clause(object, 3),
+            );
+    }
     format!(
         "
     /// Load an instance based on its identifier
-    pub fn load(connection: &mut SqliteConnection, identifier: i32) -> Result<{object_name}, bdmg::Error> {{
+    pub fn load(connection: &mut SqliteConnection, identifier: {id_type}) -> Result<{object_name}, bdmg::Error> {{
         let result = {table_name}::dsl::{table_name}
             {select_clause}
             .filter({table_name}::id.eq(identifier))
@@ -355,6 +799,71 @@ fn loadfn(object: &Object) -> String {
         table_name = object.get_table_name(),
         select_clause = super::generate_rust_select_clause(object, 3),
         load_from_attribute = loaders,
+        id_type = super::get_id_type(object.get_id_type()),
+    )
+}
+
+/// Generate the loader of a unique group having optional attributes. The database does not
+/// enforce the uniqueness of the combination when one of the attributes is NULL, so the
+/// generated function returns the first matching instance in that case
+fn optional_group_loader(object: &Object, group: &[String], attributes: &[&Attribute]) -> String {
+    let mut params = String::new();
+    let mut filters = String::new();
+    for at in attributes {
+        let parameter = format!("a_{}", at.get_name());
+        params += &format!(", {parameter}: {}", super::get_rust_borrowed_type(at));
+        if at.is_optional() {
+            filters += &optional_filter(object, at, &parameter);
+        } else {
+            let value = match at.get_reference() {
+                Some(_referenced_name) => format!("{parameter}.id().id"),
+                None => format!("&{parameter}"),
+            };
+            filters += &format!(
+                "\n        let query = query.filter({table_name}::{attribute_name}.eq({value}));",
+                attribute_name = at.get_name(),
+                table_name = object.get_table_name(),
+            );
+        }
+    }
+    format!(
+        "
+    /// Load an instance based on the attributes {attribute_names}.
+    /// An attribute without value matches the instance having no value for it
+    pub fn load_from_{function_suffix}(connection: &mut SqliteConnection{params}) -> Result<{object_name}, bdmg::Error> {{
+        let query = {table_name}::dsl::{table_name}.into_boxed();{filters}
+        let mut result = query.limit(1).load::<{object_name}>(connection)?;
+        match result.len() {{
+            1 => Ok(result.pop().unwrap()),
+            _ => Err(bdmg::Error::ElementNotFound)
+        }}
+    }}",
+        attribute_names = group.join(", "),
+        function_suffix = group.join("_and_"),
+        object_name = object.get_name(),
+        table_name = object.get_table_name(),
+    )
+}
+
+/// Generate the statement filtering the boxed `query` on the value of an optional attribute,
+/// given by the parameter. As NULL is never equal to a value, the instances without value
+/// are matched with IS NULL
+fn optional_filter(object: &Object, attribute: &Attribute, parameter: &str) -> String {
+    let value = match attribute.get_reference() {
+        Some(_referenced_name) => format!("{parameter}.map(|a| a.id().id)"),
+        None => match attribute.get_type().get_base_type() {
+            BaseAttributeType::String => format!("{parameter}.as_deref()"),
+            _ => format!("{parameter}.as_ref()"),
+        },
+    };
+    format!(
+        "
+        let query = match {value} {{
+            Some(value) => query.filter({table_name}::{attribute_name}.eq(value)),
+            None => query.filter({table_name}::{attribute_name}.is_null()),
+        }};",
+        attribute_name = attribute.get_name(),
+        table_name = object.get_table_name(),
     )
 }
 
@@ -370,6 +879,15 @@ fn deletefn(object: &Object) -> String {
     )
 }
 
+/// Retrieve the type of the parameter of an attribute given at creation: an attribute
+/// with a default value may be omitted
+fn get_creation_param_type(attribute: &Attribute) -> String {
+    match attribute.get_default() {
+        Some(_) => format!("Option<{}>", super::get_rust_param_type(attribute)),
+        None => super::get_rust_param_type(attribute),
+    }
+}
+
 fn newfn(object: &Object) -> String {
     let function_params = {
         //declaration of function parameters
@@ -385,7 +903,7 @@ fn newfn(object: &Object) -> String {
                             + &format!(
                                 "\n        a_{}: {},",
                                 at.get_name(),
-                                super::get_rust_param_type(at)
+                                get_creation_param_type(at)
                             );
                     }
                 },
@@ -468,7 +986,7 @@ fn createfn(object: &Object) -> String {
                             + &format!(
                                 "\n        a_{}: {},",
                                 at.get_name(),
-                                super::get_rust_param_type(at)
+                                get_creation_param_type(at)
                             );
                     }
                 },
@@ -531,21 +1049,36 @@ fn createfn(object: &Object) -> String {
         params
     };
 
+    let mut value_checks = String::new();
+    for at in object.get_attributes() {
+        if let Some(default) = super::get_default_expression(at) {
+            value_checks += &format!(
+                "\n        let a_{name} = match a_{name} {{ Some(v) => v, None => {default} }};",
+                name = at.get_name()
+            );
+        }
+    }
+    for at in object.get_attributes() {
+        value_checks += &value_check(at, &format!("a_{}", at.get_name()));
+    }
+
     let validator = match object.get_validator() {
         None => String::new(),
         Some(function) => format!("if ! {function}(connection, &t) {{ return Err(bdmg::Error::UnableToCreateRecord(format!(\"validation failed for {{:?}}\", t))); }}")
     };
 
+    let (new_id, insertable_id, invalid_id) = new_id_expressions(object);
     let insertable_creation = match object.get_validator() {
         None => format!(
-            "let tmp = Insertable{object_name} {{{constructor_param}
+            "let tmp = Insertable{object_name} {{{insertable_id}{constructor_param}
             version: 0,
         }};",
+            insertable_id = insertable_id.replace("\n", "\n            "),
             object_name = object.get_name()
         ),
         Some(_function) => format!(
             "let t = {object_name} {{
-            id: 0,{constructor_param}
+            id: {new_id},{constructor_param}
             version: 0,
         }};
         {validator}
@@ -559,13 +1092,13 @@ fn createfn(object: &Object) -> String {
     /// Create a new instance of {object_name} based on the ids of the references (if any)
     pub fn create(
         connection: &mut SqliteConnection,{function_params}
-    ) -> Result<{object_name}, bdmg::Error> {{
+    ) -> Result<{object_name}, bdmg::Error> {{{value_checks}
         {insertable_creation}
 
         let result = diesel::insert_into({table_name}::table)
             .values(&tmp)
             .returning({table_name}::id)
-            .get_result::<i32>(connection);
+            .get_result::<{id_type}>(connection);
 
         match result {{
             Ok(id) => {{
@@ -574,16 +1107,33 @@ fn createfn(object: &Object) -> String {
             Err(e) => {{
                 Err(bdmg::Error::UnableToCreateRecord(format!(
                     \"Error while creating instance {{:?}}: {{e}}\",
-                    {object_name}::from((-1, tmp))
+                    {object_name}::from(({invalid_id}, tmp))
                 )))
             }}
         }}
     }}",
         object_name = object.get_name(),
         table_name = object.get_table_name(),
+        id_type = super::get_id_type(object.get_id_type()),
     )
 }
 
+/// Retrieve the expressions used to give an identifier to a new instance:
+/// the value of the id of the instance before its insertion, the initialisation
+/// of the id in the insertable structure (if any) and the id shown when the
+/// insertion failed
+fn new_id_expressions(object: &Object) -> (&'static str, &'static str, &'static str) {
+    match object.get_id_type() {
+        //the database gives the identifier during the insertion
+        IdentifierType::Integer => ("0", "", "-1"),
+        IdentifierType::Uuid => (
+            "bdmg::Uuid::new_v4()",
+            "\nid: bdmg::Uuid::new_v4(),",
+            "tmp.id",
+        ),
+    }
+}
+
 fn mass_create(object: &Object) -> String {
     let pair_type = {
         //declaration of function parameters
@@ -642,21 +1192,28 @@ fn mass_create(object: &Object) -> String {
         params
     };
 
+    let mut value_checks = String::new();
+    for (index, at) in object.get_attributes().enumerate() {
+        value_checks += &value_check(at, &format!("element.{index}"));
+    }
+
     let validator = match object.get_validator() {
         None => String::new(),
         Some(function) => format!("if ! {function}(connection, &t) {{ return Err(bdmg::Error::UnableToCreateRecord(String::from(\"validation failed\"))); }}")
     };
 
+    let (new_id, insertable_id, _invalid_id) = new_id_expressions(object);
     let insertable_creation = match object.get_validator() {
         None => format!(
-            "let tmp = Insertable{object_name} {{{constructor_param}
+            "let tmp = Insertable{object_name} {{{insertable_id}{constructor_param}
                 version: 0,
             }};",
+            insertable_id = insertable_id.replace("\n", "\n                "),
             object_name = object.get_name()
         ),
         Some(_function) => format!(
             "let t = {object_name} {{
-            id: 0,{constructor_param}
+            id: {new_id},{constructor_param}
                 version: 0,
             }};
             {validator}
@@ -676,7 +1233,7 @@ fn mass_create(object: &Object) -> String {
             return Ok(());
         }}
         let mut new_values = Vec::with_capacity(values.len());
-        for element in values {{
+        for element in values {{{value_checks}
             {insertable_creation}
             new_values.push(tmp);
         }}
@@ -693,6 +1250,38 @@ fn mass_create(object: &Object) -> String {
     )
 }
 
+/// Retrieve the name of the function loading the instances of the referencing object that
+/// reference the object. When the object is referenced through multiple attributes, the
+/// function is qualified by the attribute
+fn referencing_function_name(referencing_name: &str, attribute: &Attribute, qualified: bool) -> String {
+    let referencing_snake_name = super::get_snake_name(referencing_name);
+    if qualified {
+        format!("get_{}s_as_{}", referencing_snake_name, attribute.get_name())
+    } else {
+        format!("get_{}s", referencing_snake_name)
+    }
+}
+
+/// Retrieve the name of the function loading the instances of the destination object that
+/// are related to the object through a relation object
+fn related_function_name(destination_name: &str, relation_name: &str, attribute: &Attribute, qualified: bool) -> String {
+    let destination_snake_name = super::get_snake_name(destination_name);
+    let relation_snake_name = super::get_snake_name(relation_name);
+    if qualified {
+        format!("get_{}s_from_{}s_as_{}", destination_snake_name, relation_snake_name, attribute.get_name())
+    } else {
+        format!("get_{}s_from_{}s", destination_snake_name, relation_snake_name)
+    }
+}
+
+/// Check if the attribute of the referencing object is the parent of the hierarchy of the object
+fn is_hierarchy_reference(object: &Object, referencing_name: &str, attribute: &Attribute) -> bool {
+    referencing_name == object.get_name()
+        && object
+            .get_hierarchy_attribute()
+            .is_some_and(|parent| parent.get_name() == attribute.get_name())
+}
+
 fn get_relations(object: &Object, db: &ObjectDB) -> String {
     let mut code = String::new();
     for referencing_name in object.get_referencing_objects() {
@@ -700,16 +1289,17 @@ fn get_relations(object: &Object, db: &ObjectDB) -> String {
             Some(obj) => obj,
             None => continue,
         };
-        let attribute_referencing_object =
-            match referencing.get_relation_attribute(object.get_name()) {
-                Some(at) => at.get_name(),
-                None => continue,
-            };
-        let referencing_snake_name = super::get_snake_name(referencing_name);
-        let function_name = format!("get_{}s", referencing_snake_name);
-        let select_clause = super::generate_rust_select_clause(referencing, 4);
-        code = format!(
-            "{existing}    ///Retrieve all {referencing_name} referencing this object
+        //when the object is referenced through multiple attributes, the functions are qualified by the attribute
+        let attributes = referencing.get_relation_attributes(object.get_name());
+        for attribute in &attributes {
+            //the children of a hierarchy are retrieved, in order, by get_children
+            if is_hierarchy_reference(object, referencing_name, attribute) {
+                continue;
+            }
+            let function_name = referencing_function_name(referencing_name, attribute, attributes.len() > 1);
+            let select_clause = super::generate_rust_select_clause(referencing, 4);
+            code = format!(
+                "{existing}    ///Retrieve all {referencing_name} referencing this object through {attribute_name}
     pub fn {function_name}(
         self: &{object_name},
         connection: &mut SqliteConnection,
@@ -719,37 +1309,30 @@ fn get_relations(object: &Object, db: &ObjectDB) -> String {
             .filter(super::schema::{referencing_table}::{attribute_name}.eq(self.id))
             .load::<super::{referencing_name}>(connection)?)
     }}\n",
-            existing = code,
-            function_name = function_name,
-            referencing_name = referencing_name,
-            object_name = object.get_name(),
-            referencing_table = referencing.get_table_name(),
-            select = select_clause,
-            attribute_name = attribute_referencing_object
-        );
+                existing = code,
+                function_name = function_name,
+                referencing_name = referencing_name,
+                object_name = object.get_name(),
+                referencing_table = referencing.get_table_name(),
+                select = select_clause,
+                attribute_name = attribute.get_name()
+            );
+        }
 
-        match referencing.is_object_relation() {
-            None => {}
-            Some((a, b)) => {
-                let dest = if a == object.get_name() {
-                    db.get_object(b)
-                } else {
-                    db.get_object(a)
-                };
-                match dest {
-                    None => {}
-                    Some(dest_object) => {
-                        let destination_object_name = dest_object.get_name();
-                        let function_name = format!(
-                            "get_{}s_from_{}s",
-                            super::get_snake_name(destination_object_name),
-                            referencing_snake_name
-                        );
-                        let mut selected_attributes = String::new();
-                        for at in dest_object.get_attributes() {
-                            selected_attributes = format!("{selected_attributes}                    super::schema::{destination_table}::{attribute_name},\n", destination_table = dest_object.get_table_name(), attribute_name = at.get_name());
-                        }
-                        code = format!("{existing}\n    ///Retrieve all {destination_name} that related to this object through {referencing_name}
+        let sides = referencing.get_relation_sides(object.get_name());
+        for (attribute, other_attribute) in &sides {
+            let dest_object = match other_attribute.get_reference().and_then(|r| db.get_object(r)) {
+                Some(dest_object) => dest_object,
+                None => continue,
+            };
+            let destination_object_name = dest_object.get_name();
+            let function_name = related_function_name(destination_object_name, referencing_name, attribute, sides.len() > 1);
+            let mut selected_attributes = String::new();
+            for at in dest_object.get_attributes() {
+                selected_attributes = format!("{selected_attributes}                    super::schema::{destination_table}::{attribute_name},\n", destination_table = dest_object.get_table_name(), attribute_name = at.get_name());
+            }
+            //the join is explicit, as the relation may reference the destination through multiple attributes
+            code = format!("{existing}\n    ///Retrieve all {destination_name} that related to this object through {referencing_name}
     pub fn {function_name}(
         self: &{object_name},
         connection: &mut SqliteConnection,
@@ -759,23 +1342,94 @@ fn get_relations(object: &Object, db: &ObjectDB) -> String {
                 super::schema::{destination_table}::id,
 {selected_attributes}                    super::schema::{destination_table}::version,
             ))
-            .inner_join(super::schema::{rel_table}::table)
+            .inner_join(super::schema::{rel_table}::table.on(super::schema::{rel_table}::{other_att_name}.eq(super::schema::{destination_table}::id)))
             .filter(super::schema::{rel_table}::{att_name}.eq(self.id))
             .load::<super::{destination_name}>(connection)?)
-    }}\n", 
-                            existing = code,
-                            function_name = function_name,
-                            object_name = object.get_name(),
-                            referencing_name = referencing_name,
-                            destination_name = destination_object_name,
-                            destination_table = dest_object.get_table_name(),
-                            rel_table = referencing.get_table_name(),
-                            att_name = referencing.get_relation_attribute(object.get_name()).unwrap().get_name()
-                        );
-                    }
-                }
-            }
+    }}\n",
+                existing = code,
+                function_name = function_name,
+                object_name = object.get_name(),
+                referencing_name = referencing_name,
+                destination_name = destination_object_name,
+                destination_table = dest_object.get_table_name(),
+                rel_table = referencing.get_table_name(),
+                att_name = attribute.get_name(),
+                other_att_name = other_attribute.get_name()
+            );
         }
     }
     code
 }
+
+/// Generate the functions navigating the hierarchy formed by the object, if any.
+/// The ancestors and the descendants are retrieved with recursive queries. The
+/// queries use UNION, so that they end even if the database contains a cycle
+fn hierarchyfn(object: &Object) -> String {
+    let parent = match object.get_hierarchy_attribute() {
+        Some(parent) => parent.get_name(),
+        None => return String::new(),
+    };
+    format!(
+        "
+    ///Retrieve the instances whose {parent} is this object
+    pub fn get_children(&self, connection: &mut SqliteConnection) -> Result<Vec<{object_name}>, bdmg::Error> {{
+        Ok({table_name}::dsl::{table_name}
+            {select_clause}
+            .filter({table_name}::{parent}.eq(self.id))
+            .order({table_name}::id.asc())
+            .load::<{object_name}>(connection)?)
+    }}
+
+    ///Retrieve the ancestors of this object, starting with its {parent} up to the root of the hierarchy
+    pub fn get_ancestors(&self, connection: &mut SqliteConnection) -> Result<Vec<{object_name}>, bdmg::Error> {{
+        let ancestors = {table_name}::dsl::{table_name}
+            {select_clause}
+            .filter(
+                diesel::dsl::sql::<diesel::sql_types::Bool>(\"{table_name}.id IN (WITH RECURSIVE bdmg_hierarchy(id) AS (SELECT {parent} FROM {table_name} WHERE id = \")
+                    .bind::<diesel::sql_types::{id_sql_type}, _>(self.id)
+                    .sql(\" UNION SELECT {table_name}.{parent} FROM {table_name} JOIN bdmg_hierarchy ON {table_name}.id = bdmg_hierarchy.id) SELECT id FROM bdmg_hierarchy)\"),
+            )
+            .load::<{object_name}>(connection)?;
+        //the ancestors are ordered by following the parents
+        let mut ancestors: std::collections::HashMap<_, _> = ancestors.into_iter().map(|ancestor| (ancestor.id, ancestor)).collect();
+        let mut result = Vec::with_capacity(ancestors.len());
+        let mut current = self.{parent};
+        while let Some(ancestor) = current.and_then(|id| ancestors.remove(&id)) {{
+            current = ancestor.{parent};
+            result.push(ancestor);
+        }}
+        Ok(result)
+    }}
+
+    ///Retrieve all the descendants of this object, ordered by id
+    pub fn get_descendants(&self, connection: &mut SqliteConnection) -> Result<Vec<{object_name}>, bdmg::Error> {{
+        Ok({table_name}::dsl::{table_name}
+            {select_clause}
+            .filter(
+                diesel::dsl::sql::<diesel::sql_types::Bool>(\"{table_name}.id IN (WITH RECURSIVE bdmg_hierarchy(id) AS (SELECT id FROM {table_name} WHERE {parent} = \")
+                    .bind::<diesel::sql_types::{id_sql_type}, _>(self.id)
+                    .sql(\" UNION SELECT {table_name}.id FROM {table_name} JOIN bdmg_hierarchy ON {table_name}.{parent} = bdmg_hierarchy.id) SELECT id FROM bdmg_hierarchy)\"),
+            )
+            .order({table_name}::id.asc())
+            .load::<{object_name}>(connection)?)
+    }}
+
+    ///Retrieve the root of the hierarchy containing this object, the object itself if it has no {parent}
+    pub fn get_root(&self, connection: &mut SqliteConnection) -> Result<{object_name}, bdmg::Error> {{
+        match self.get_ancestors(connection)?.pop() {{
+            Some(root) => Ok(root),
+            None => Ok(self.clone()),
+        }}
+    }}
+
+    ///Retrieve the number of ancestors of this object, 0 for a root
+    pub fn get_depth(&self, connection: &mut SqliteConnection) -> Result<usize, bdmg::Error> {{
+        Ok(self.get_ancestors(connection)?.len())
+    }}
+",
+        object_name = object.get_name(),
+        table_name = object.get_table_name(),
+        select_clause = super::generate_rust_select_clause(object, 3),
+        id_sql_type = super::schema::get_id_column_type(object.get_id_type()),
+    )
+}
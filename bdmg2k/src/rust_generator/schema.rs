@@ -0,0 +1,165 @@
+/*
+    Copyright 2020 benerjo
+
+    This file is part of bdmg2k.
+
+    bdmg is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    bdmg is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with bdmg.  If not, see <https://www.gnu.org/licenses/>
+*/
+
+use crate::object::Object;
+use crate::objectdb::ObjectDB;
+use crate::{Attribute, AttributeType, BaseAttributeType, IdentifierType};
+
+/// Generate the content of the schema module used by diesel.
+///
+/// The schema contains the `table!` definition of every object, a `joinable!`
+/// for every reference between two objects and the
+/// `allow_tables_to_appear_in_same_query!` listing all tables.
+pub fn generate_schema(db: &ObjectDB) -> String {
+    let mut tables = String::new();
+    let mut joinables = String::new();
+    let mut table_names = String::new();
+    let mut nb_tables = 0;
+    for object in db.get_objects() {
+        tables = format!("{tables}{table}\n", table = generate_table(object));
+        joinables = format!("{joinables}{joins}", joins = generate_joinables(object, db));
+        table_names = format!("{table_names}\n    {table_name},", table_name = object.get_table_name());
+        nb_tables += 1;
+    }
+    let allow_tables = if nb_tables > 1 {
+        format!("\ndiesel::allow_tables_to_appear_in_same_query!({table_names}\n);\n")
+    } else {
+        String::new()
+    };
+    format!("// This file has been generated by bdmg2k. Any modification will be lost.\n\n{tables}{joinables}{allow_tables}")
+}
+
+/// Generate the `table!` definition of the given object
+fn generate_table(object: &Object) -> String {
+    let mut columns = String::new();
+    for at in object.get_attributes() {
+        columns = format!(
+            "{columns}        {column_name} -> {column_type},\n",
+            column_name = at.get_name(),
+            column_type = get_column_type(at)
+        );
+    }
+    format!(
+        "diesel::table! {{
+    {table_name} (id) {{
+        id -> {id_type},
+{columns}        version -> BigInt,
+    }}
+}}
+",
+        table_name = object.get_table_name(),
+        id_type = get_id_column_type(object.get_id_type())
+    )
+}
+
+/// Generate the `joinable!` declaration for every object referenced by the given object.
+/// Diesel only allows one declaration per pair of tables: when an object is referenced
+/// through multiple attributes, only the first one is declared and the other joins
+/// must be explicit. A table can not be declared joinable to itself.
+fn generate_joinables(object: &Object, db: &ObjectDB) -> String {
+    let mut joinables = String::new();
+    let mut joined: Vec<&String> = Vec::new();
+    for at in object.get_attributes() {
+        let referenced = match at.get_reference().and_then(|r| db.get_object(r)) {
+            Some(referenced) => referenced,
+            None => continue,
+        };
+        if referenced.get_name() == object.get_name() || joined.contains(&referenced.get_name()) {
+            continue;
+        }
+        joined.push(referenced.get_name());
+        joinables = format!(
+            "{joinables}diesel::joinable!({table_name} -> {referenced_table} ({column_name}));\n",
+            table_name = object.get_table_name(),
+            referenced_table = referenced.get_table_name(),
+            column_name = at.get_name()
+        );
+    }
+    joinables
+}
+
+/// Retrieve the diesel sql type used for the column of the given attribute
+fn get_column_type(attribute: &Attribute) -> String {
+    match attribute.get_type() {
+        AttributeType::Mandatory(_) => get_base_column_type(attribute),
+        AttributeType::Optional(_) => format!("Nullable<{}>", get_base_column_type(attribute)),
+    }
+}
+
+/// Retrieve the diesel sql type used to store an identifier
+pub(super) fn get_id_column_type(id_type: IdentifierType) -> String {
+    match id_type {
+        IdentifierType::Integer => String::from("Integer"),
+        IdentifierType::Uuid => String::from("Text"),
+    }
+}
+
+/// Retrieve the diesel sql type used to store the base type of the attribute
+fn get_base_column_type(attribute: &Attribute) -> String {
+    match attribute.get_type().get_base_type() {
+        BaseAttributeType::Integer => String::from("BigInt"),
+        BaseAttributeType::Float => String::from("Double"),
+        BaseAttributeType::Decimal { .. } => String::from("Text"),
+        BaseAttributeType::Boolean => String::from("Bool"),
+        BaseAttributeType::String => String::from("Text"),
+        BaseAttributeType::Date => String::from("Date"),
+        BaseAttributeType::Time => String::from("Time"),
+        BaseAttributeType::Timestamp => String::from("TimestamptzSqlite"),
+        BaseAttributeType::Duration => String::from("BigInt"),
+        BaseAttributeType::Bytes => String::from("Binary"),
+        BaseAttributeType::Uuid => String::from("Text"),
+        BaseAttributeType::Json | BaseAttributeType::TypedJson(_) => String::from("Text"),
+        BaseAttributeType::Enum(_) => String::from("Text"),
+        BaseAttributeType::Reference(_) => get_id_column_type(attribute.get_reference_id_type()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::Object;
+
+    use super::generate_table;
+
+    #[test]
+    fn table_definition() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"books\", \"name\": \"Book\", \"attr\": [
+                {\"name\": \"title\", \"is\": {\"Mandatory\": \"String\"}},
+                {\"name\": \"pages\", \"is\": {\"Optional\": \"Integer\"}},
+                {\"name\": \"author\", \"is\": {\"Mandatory\": {\"Reference\": \"Author\"}}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "diesel::table! {
+    books (id) {
+        id -> Integer,
+        title -> Text,
+        pages -> Nullable<BigInt>,
+        author -> Integer,
+        version -> BigInt,
+    }
+}
+"
+            ),
+            generate_table(&object)
+        );
+    }
+}
@@ -61,7 +61,28 @@ fn generate_object_introspection_traits_impl(object: &Object, db: &ObjectDB) ->
         if !at.is_secret() {
             let attribute_type = match at.get_type().get_base_type() {
                 BaseAttributeType::Integer => String::from("Integer"),
+                BaseAttributeType::Float => String::from("Float"),
+                BaseAttributeType::Decimal { precision, scale } => {
+                    format!("Decimal {{ precision: {precision}, scale: {scale} }}")
+                }
+                BaseAttributeType::Boolean => String::from("Boolean"),
                 BaseAttributeType::String => String::from("String"),
+                BaseAttributeType::Date => String::from("Date"),
+                BaseAttributeType::Time => String::from("Time"),
+                BaseAttributeType::Timestamp => String::from("Timestamp"),
+                BaseAttributeType::Duration => String::from("Duration"),
+                BaseAttributeType::Bytes => String::from("Bytes"),
+                BaseAttributeType::Uuid => String::from("Uuid"),
+                BaseAttributeType::Json | BaseAttributeType::TypedJson(_) => String::from("Json"),
+                BaseAttributeType::Enum(e) => {
+                    let mut values = String::new();
+                    if let Some(enumeration) = db.get_enum(e) {
+                        for variant in enumeration.get_variants() {
+                            values = format!("{values}String::from(\"{}\"), ", variant.get_name());
+                        }
+                    }
+                    format!("Enum {{ name: String::from(\"{e}\"), values: vec![{values}] }}")
+                }
                 BaseAttributeType::Reference(r) => {
                     format!("Reference(String::from(\"{}\"))", r)
                 }
@@ -85,8 +106,9 @@ fn generate_object_introspection_traits_impl(object: &Object, db: &ObjectDB) ->
         atdef = atdef + "            " + at.get_name() + ": None,\n";
     }
     let object_name = object.get_name();
+    let id_type = super::get_id_type(object.get_id_type());
     let get_object_code = format!(
-        "match {object_name}::load(connection, id) {{
+        "match {object_name}::load(connection, <{id_type}>::try_from(id)?) {{
             Ok(obj) => {{
                 if version.is_none() || obj.get_version() == version.unwrap() {{
                     Ok(Box::new(obj))
@@ -122,7 +144,7 @@ fn generate_object_introspection_traits_impl(object: &Object, db: &ObjectDB) ->
     fn get_object(
         &self,
         connection: &mut diesel::sqlite::SqliteConnection,
-        id: i32,
+        id: bdmg::ObjectId,
         version: Option<i64>
     ) -> Result<Box<(dyn Object + 'static)>, bdmg::Error> {{
         {get_object_code}
@@ -154,42 +176,39 @@ fn generate_object_introspection_traits_impl(object: &Object, db: &ObjectDB) ->
 }
 
 fn get_relations(object: &Object, db: &ObjectDB) -> String {
-    let mut code = String::from("fn get_related(
+    let mut code = format!("fn get_related(
         &self,
         connection: &mut diesel::sqlite::SqliteConnection,
-        instance_id: i32,
+        instance_id: bdmg::ObjectId,
         related_object: &str,
         relation_object: &str,
         referencing_attribute: &str,
-    ) -> Result<Vec<Box<dyn Object>>, bdmg::Error> {
-        ");
+    ) -> Result<Vec<Box<dyn Object>>, bdmg::Error> {{
+        let instance_id = <{id_type}>::try_from(instance_id)?;
+        ", id_type = super::get_id_type(object.get_id_type()));
     let mut nb_relations_found = 0;
     for referencing_name in object.get_referencing_objects() {
         let relation_object = match db.get_object(referencing_name) {
             Some(referencing_object) => referencing_object,
             None => continue,
         };
-        let other_object = match relation_object.is_object_relation() {
-            Some((source, destination)) => {
-                if source == object.get_name() {
-                    db.get_object(destination).unwrap()
-                } else {
-                    db.get_object(source).unwrap()
-                }
-            },
-            None => continue,
-        };
-        let attribute_name = relation_object.get_relation_attribute(object.get_name()).unwrap().get_name();
-        let other_object_name = other_object.get_name();
-        let other_table_name = other_object.get_table_name();
-        let mut selected_attributes = String::new();
-        for at in other_object.get_attributes() {
-            selected_attributes = format!("{selected_attributes}                    super::schema::{other_table_name}::{attribute_name},\n", attribute_name = at.get_name());
-        }
-        
-        let relation_table = relation_object.get_table_name();
+        for (attribute, other_attribute) in relation_object.get_relation_sides(object.get_name()) {
+            let other_object = match other_attribute.get_reference().and_then(|r| db.get_object(r)) {
+                Some(other_object) => other_object,
+                None => continue,
+            };
+            let attribute_name = attribute.get_name();
+            let other_attribute_name = other_attribute.get_name();
+            let other_object_name = other_object.get_name();
+            let other_table_name = other_object.get_table_name();
+            let mut selected_attributes = String::new();
+            for at in other_object.get_attributes() {
+                selected_attributes = format!("{selected_attributes}                    super::schema::{other_table_name}::{attribute_name},\n", attribute_name = at.get_name());
+            }
 
-        code = format!("{code} if relation_object == \"{referencing_name}\"
+            let relation_table = relation_object.get_table_name();
+
+            code = format!("{code} if relation_object == \"{referencing_name}\"
             && referencing_attribute == \"{attribute_name}\"
             && related_object == \"{other_object_name}\"
         {{
@@ -200,7 +219,7 @@ fn get_relations(object: &Object, db: &ObjectDB) -> String {
                     super::schema::{other_table_name}::version,
                 ))
                 .distinct()
-                .inner_join(super::schema::{relation_table}::dsl::{relation_table})
+                .inner_join(super::schema::{relation_table}::dsl::{relation_table}.on(super::schema::{relation_table}::{other_attribute_name}.eq(super::schema::{other_table_name}::id)))
                 .filter(super::schema::{relation_table}::dsl::{attribute_name}.eq(instance_id))
                 .order(super::schema::{other_table_name}::id.asc())
                 .load::<super::{other_object_name}>(connection)?;
@@ -210,7 +229,8 @@ fn get_relations(object: &Object, db: &ObjectDB) -> String {
             }}
             Ok(result)
         }} else ");
-        nb_relations_found += 1;
+            nb_relations_found += 1;
+        }
     }
     if nb_relations_found > 0 {
         format!("{code} {{ 
@@ -221,7 +241,7 @@ fn get_relations(object: &Object, db: &ObjectDB) -> String {
         String::from("fn get_related(
             &self,
             _connection: &mut diesel::sqlite::SqliteConnection,
-            _instance_id: i32,
+            _instance_id: bdmg::ObjectId,
             _related_object: &str,
             _relation_object: &str,
             _referencing_attribute: &str,
@@ -236,37 +256,35 @@ fn get_referencing(object: &Object, db: &ObjectDB) -> String {
         return String::from("fn get_referencing(
         &self,
         _connection: &mut diesel::sqlite::SqliteConnection,
-        _instance_id: i32,
+        _instance_id: bdmg::ObjectId,
         _ref_table: &str,
         _ref_attribute: &str,
     ) -> Result<Vec<Box<dyn bdmg::Object>>, bdmg::Error> {
         Err(bdmg::Error::ElementNotFound)
     }")
     }
-    let mut code = String::from("fn get_referencing(
+    let mut code = format!("fn get_referencing(
         &self,
         connection: &mut diesel::sqlite::SqliteConnection,
-        instance_id: i32,
+        instance_id: bdmg::ObjectId,
         ref_table: &str,
         ref_attribute: &str,
-    ) -> Result<Vec<Box<dyn bdmg::Object>>, bdmg::Error> {
-        ");
+    ) -> Result<Vec<Box<dyn bdmg::Object>>, bdmg::Error> {{
+        let instance_id = <{id_type}>::try_from(instance_id)?;
+        ", id_type = super::get_id_type(object.get_id_type()));
     for referencing_name in object.get_referencing_objects() {
         let referencing_object = match db.get_object(referencing_name) {
             Some(referencing_object) => referencing_object,
             None => continue,
         };
-        let attribute_referencing_object =
-            match referencing_object.get_relation_attribute(object.get_name()) {
-                Some(at) => at.get_name(),
-                None => continue,
-            };
         let referencing_table_name = referencing_object.get_table_name();
         let mut selected_attributes = String::new();
         for at in referencing_object.get_attributes() {
             selected_attributes = format!("{selected_attributes}                    super::schema::{referencing_table_name}::{attribute_name},\n", attribute_name = at.get_name());
         }
-        let branch = format!("if ref_table == \"{referencing_name}\" && ref_attribute == \"{attribute_referencing_object}\" {{
+        for attribute in referencing_object.get_relation_attributes(object.get_name()) {
+            let attribute_referencing_object = attribute.get_name();
+            let branch = format!("if ref_table == \"{referencing_name}\" && ref_attribute == \"{attribute_referencing_object}\" {{
             let sql_result: Vec<super::{referencing_name}> = super::schema::{referencing_table_name}::dsl::{referencing_table_name}
                 .select((
                     super::schema::{referencing_table_name}::id,
@@ -281,7 +299,8 @@ fn get_referencing(object: &Object, db: &ObjectDB) -> String {
             }}
             Ok(result)
         }} else ");
-        code = format!("{code}{branch}");
+            code = format!("{code}{branch}");
+        }
     }
 
     format!("{code} {{
@@ -302,13 +321,10 @@ fn backreferencing(object: &Object, db: &ObjectDB) -> String {
             Some(obj) => obj,
             None => continue,
         };
-        let attribute_referencing_object =
-            match referencing.get_relation_attribute(object.get_name()) {
-                Some(at) => at.get_name(),
-                None => continue,
-            };
-        code = format!("{code}\n            bdmg::BackReference::new(super::{}::get_object_introspection(), String::from(\"{}\")),",
-            referencing.get_name(), attribute_referencing_object);
+        for attribute in referencing.get_relation_attributes(object.get_name()) {
+            code = format!("{code}\n            bdmg::BackReference::new(super::{}::get_object_introspection(), String::from(\"{}\")),",
+                referencing.get_name(), attribute.get_name());
+        }
     }
     format!("{code}\n        ]\n    }}")
 }
@@ -361,16 +377,22 @@ fn generate_rust_traits_get_object_iter(object: &Object) -> String {
         &self,
         connection: &'a mut diesel::sqlite::SqliteConnection,
     ) -> ObjectIterator<'a> {{
-        let max_id = match {table_name}::dsl::{table_name}.select({table_name}::id)
+        let min_id = {table_name}::dsl::{table_name}.select({table_name}::id)
+                         .order({table_name}::id.asc())
+                         .limit(1)
+                         .get_result::<{id_type}>(connection)
+                         .ok()
+                         .map(bdmg::ObjectId::from);
+        let max_id = {table_name}::dsl::{table_name}.select({table_name}::id)
                          .order({table_name}::id.desc())
                          .limit(1)
-                         .get_result::<i32>(connection) {{
-            Ok(v) => v,
-            Err(_e) => -1
-        }};
-        ObjectIterator::new(0, max_id, connection, retrieve_next_{object_lowercase}_object)
+                         .get_result::<{id_type}>(connection)
+                         .ok()
+                         .map(bdmg::ObjectId::from);
+        ObjectIterator::new(min_id, max_id, connection, retrieve_next_{object_lowercase}_object)
     }}",
         table_name = object.get_table_name(),
+        id_type = super::get_id_type(object.get_id_type()),
         object_lowercase = object.get_name().to_ascii_lowercase() /* function name */
     )
 }
@@ -427,8 +449,8 @@ fn generate_rust_traits_impl_boxed_introspection(object: &Object) -> String {
 fn generate_object_traits_impl(object: &Object) -> String {
     format!(
         "impl Object for {object_name} {{
-    fn get_id(&self) -> i32 {{
-        self.id
+    fn get_id(&self) -> bdmg::ObjectId {{
+        bdmg::ObjectId::from(self.id)
     }}
     fn get_version(&self) -> i64 {{
         self.version
@@ -470,23 +492,17 @@ fn generate_traits_impl_object_get_attr(object: &Object) -> String {
                 at.get_name(),
                 {
                     match at.get_type() {
-                        AttributeType::Mandatory(base_type) => match base_type {
-                            BaseAttributeType::Integer => {
-                                format!("Ok(format!(\"{{}}\",self.get_{}()))", at.get_name())
-                            }
-                            BaseAttributeType::String => {
-                                format!("Ok(self.get_{}().clone())", at.get_name())
-                            }
-                            BaseAttributeType::Reference(_r) => {
-                                format!("Ok(format!(\"{{}}\", self.{}))", at.get_name())
-                            }
-                        },
-                        AttributeType::Optional(_base_type) => format!(
+                        AttributeType::Mandatory(base_type) => format!(
+                            "Ok({})",
+                            get_string_representation(base_type, &format!("self.{}", at.get_name()))
+                        ),
+                        AttributeType::Optional(base_type) => format!(
                             "match &self.{} {{
-                Some(v) => Ok(format!(\"({{}})\", v)),
+                Some(v) => Ok(format!(\"({{}})\", {})),
                 None => Ok(String::new())
             }}",
-                            at.get_name()
+                            at.get_name(),
+                            get_string_representation(base_type, "v")
                         ),
                     }
                 }
@@ -498,19 +514,22 @@ fn generate_traits_impl_object_get_attr(object: &Object) -> String {
         }}")
 }
 
+/// Retrieve the expression converting the given value into its string representation.
+/// The string representation must be parsable by the setters.
+fn get_string_representation(base_type: &BaseAttributeType, value: &str) -> String {
+    match base_type {
+        BaseAttributeType::String => format!("{value}.clone()"),
+        BaseAttributeType::Timestamp => format!("{value}.to_rfc3339()"),
+        BaseAttributeType::Bytes => format!("bdmg::Base64::encode(&{value})"),
+        _ => format!("{value}.to_string()"),
+    }
+}
+
 fn generate_traits_impl_object_set_attr(object: &Object) -> String {
     let mut matches = String::new();
     for at in object.get_attributes() {
         if at.is_mutable() {
-            let value_expression = match at.get_type() {
-                AttributeType::Mandatory(_) => {
-                    format!("_value.parse::<{}>()", super::get_rust_type(at))
-                }
-                AttributeType::Optional(base) => format!(
-                    "bdmg::extract_optional::<{}>(_value)",
-                    super::get_base_type(base)
-                ),
-            };
+            let value_expression = super::get_parse_expression(at, "_value");
             let set_expression = match at.get_type().get_base_type() {
                 BaseAttributeType::Reference(r) => match at.get_type() {
                     AttributeType::Mandatory(_) => {
@@ -597,10 +616,7 @@ fn generate_object_factory_traits_impl_setter(object: &Object) -> String {
                 }
             },
             _ => {
-                let value_retriever = match at.get_type() {
-                    AttributeType::Mandatory(_) => format!("attribute_value.parse::<{}>()", super::get_rust_type(at)),
-                    AttributeType::Optional(base) => format!("bdmg::extract_optional::<{}>(attribute_value)", super::get_base_type(base))
-                };
+                let value_retriever = super::get_parse_expression(at, "attribute_value");
                 format!(
 "                let value = {value};
             match value {{
@@ -629,6 +645,8 @@ fn generate_object_factory_traits_impl_create(object: &Object) -> String {
     let mut constr = String::new();
     for at in object.get_attributes() {
         let not_found_case = match at.get_type() {
+            //the default value is given by the create function
+            AttributeType::Mandatory(_) if at.get_default().is_some() => String::from("None"),
             AttributeType::Mandatory(_) => {
                 format!("return Err(bdmg::Error::MissingMandatoryAttribute(String::from(\"{attribute_name}\")))", attribute_name = at.get_name())
             }
@@ -638,6 +656,7 @@ fn generate_object_factory_traits_impl_create(object: &Object) -> String {
         };
 
         let found_case = match at.get_reference() {
+            None if at.get_default().is_some() => format!("Some(_) => self.{}.take()", at.get_name()),
             None => format!("Some(_) => self.{}.take().unwrap()", at.get_name()),
             Some(referenced_type) => match at.get_type() {
                 AttributeType::Mandatory(_v) => {
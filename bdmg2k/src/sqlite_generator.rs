@@ -16,13 +16,60 @@
     You should have received a copy of the GNU Affero General Public License
     along with bdmg.  If not, see <https://www.gnu.org/licenses/>
 */
-use crate::{Object, ObjectDB, Error};
+use crate::model_diff;
+use crate::{Attribute, BaseAttributeType, Error, IdentifierType, Object, ObjectDB};
 
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn write_install(object_db: &ObjectDB, destination: &str, script_name: &str) -> Result<(), Error> {
+    let pbuf = create_output_directory(destination)?;
+    write_sqlite_file(&pbuf, script_name, generate_sqlite_install(object_db))
+}
+
+/// Write the scripts migrating a database from the old version of the model to the new
+/// one and back, as `up.sql` and `down.sql` in a directory named after the migration
+pub fn write_migration(old: &ObjectDB, new: &ObjectDB, destination: &str, migration_name: &str) -> Result<(), Error> {
+    let pbuf = create_output_directory(destination)?;
+    let migration_destination = pbuf.join(migration_name).display().to_string();
+    let migration_dir = create_output_directory(&migration_destination)?;
+    let (up, down) = generate_sqlite_migration(old, new)?;
+    write_sqlite_file(&migration_dir, "up", up)?;
+    write_sqlite_file(&migration_dir, "down", down)
+}
+
+/// Read the migrations held by a directory, as written by write_migration: the name, the up
+/// script and the down script of each migration, in the order of the migration names
+pub fn read_migrations(directory: &str) -> Result<Vec<(String, String, String)>, Error> {
+    let read_error = |file: &Path, error| Error::UnableToReadFile {
+        file: file.display().to_string(),
+        error,
+    };
+    let entries = std::fs::read_dir(directory).map_err(|e| read_error(Path::new(directory), e))?;
+    let mut migration_dirs = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| read_error(Path::new(directory), e))?.path();
+        if path.is_dir() {
+            migration_dirs.push(path);
+        }
+    }
+    migration_dirs.sort();
+
+    let mut migrations = Vec::with_capacity(migration_dirs.len());
+    for migration_dir in migration_dirs {
+        let read_script = |script_name: &str| {
+            let file = migration_dir.join(script_name);
+            std::fs::read_to_string(&file).map_err(|e| read_error(&file, e))
+        };
+        let name = migration_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        migrations.push((name, read_script("up.sql")?, read_script("down.sql")?));
+    }
+    Ok(migrations)
+}
+
+/// Create the output directory, if it does not exist yet
+fn create_output_directory(destination: &str) -> Result<PathBuf, Error> {
     let pbuf = PathBuf::from(destination);
 
     if !pbuf.exists() {
@@ -37,10 +84,12 @@ pub fn write_install(object_db: &ObjectDB, destination: &str, script_name: &str)
             destination: String::from(destination),
         });
     }
+    Ok(pbuf)
+}
 
-    let content = generate_sqlite_install(object_db);
-
-    let (mut sql_file, filename) = get_sqlite_file(&pbuf, script_name)?;
+/// Write a sql script in the output directory
+fn write_sqlite_file(dir: &PathBuf, script_name: &str, content: String) -> Result<(), Error> {
+    let (mut sql_file, filename) = get_sqlite_file(dir, script_name)?;
 
     match sql_file.write(content.as_bytes()) {
         Err(_e) => {
@@ -96,7 +145,7 @@ pub fn generate_sqlite_install(db: &ObjectDB) -> String {
     let mut tables = String::new();
     let mut indexes = String::new();
     for obj in db.get_objects() {
-        tables = format!("{tables}\n{obj_table}", obj_table = sqlite_table(obj));
+        tables = format!("{tables}\n{obj_table}", obj_table = sqlite_table(obj, db));
         indexes = format!(
             "{indexes}\n{obj_indexes}",
             obj_indexes = sqlite_indexes(obj)
@@ -105,47 +154,897 @@ pub fn generate_sqlite_install(db: &ObjectDB) -> String {
     return format!("{tables}\n{indexes}");
 }
 
-fn sqlite_table(obj: &Object) -> String {
-    let table_name = obj.get_table_name();
+/// Generate the scripts migrating a database from the old version of the model to the
+/// new one (up) and back (down). The tables are altered when the changes allow it (renamed
+/// table or columns, added columns that can be null or have a default value), and rebuilt
+/// otherwise: the new table is created and filled with the content of the previous one,
+/// keeping the identifiers and the versions. The statements must be run in a transaction,
+/// with the foreign keys disabled.
+///
+/// The changes that can not be applied to existing rows are refused, in both directions:
+/// a change of the identifier type, and a mandatory column without default value added
+/// to an existing table or replacing an optional one.
+///
+/// The tables of the removed objects are dropped first and the tables of the added objects
+/// are created last, so that a table name can be taken over by another object
+pub fn generate_sqlite_migration(old: &ObjectDB, new: &ObjectDB) -> Result<(String, String), Error> {
+    let changes: Vec<String> = old.diff(new).get_changes().map(|c| format!("\n--   {c}")).collect();
+    let changes = match changes.is_empty() {
+        true => String::from("\n--   none"),
+        false => changes.concat(),
+    };
+    let objects = model_diff::match_objects(old, new);
+    check_table_names(&objects)?;
+    //the statements dropping, migrating then creating the tables
+    let mut up = [String::new(), String::new(), String::new()];
+    let mut down = [String::new(), String::new(), String::new()];
+    for (old_object, new_object) in objects {
+        let attributes = match (old_object, new_object) {
+            (Some(old_object), Some(new_object)) => model_diff::match_attributes(old_object, new_object),
+            _ => Vec::new(),
+        };
+        let reversed: Vec<_> = attributes.iter().map(|(old_at, new_at)| (*new_at, *old_at)).collect();
+        up[migration_step(old_object, new_object)] += &sqlite_migrate_table((old_object, old), (new_object, new), &attributes)?;
+        down[migration_step(new_object, old_object)] += &sqlite_migrate_table((new_object, new), (old_object, old), &reversed)
+            .map_err(|e| match e {
+                Error::UnsupportedMigration { object, reason } => Error::UnsupportedMigration {
+                    object,
+                    reason: format!("{reason}, when reverting the migration"),
+                },
+                other => other,
+            })?;
+    }
+    Ok((
+        format!("-- Migration to the new version of the model, changes:{changes}\n{}", up.concat()),
+        format!("-- Migration back to the previous version of the model, reverting:{changes}\n{}", down.concat()),
+    ))
+}
+
+/// Retrieve the step of the migration in which the table of an object is migrated:
+/// the tables are dropped (0), then migrated (1), then created (2)
+fn migration_step(from: Option<&Object>, to: Option<&Object>) -> usize {
+    match (from, to) {
+        (Some(_from), None) => 0,
+        (Some(_from), Some(_to)) => 1,
+        (None, _) => 2,
+    }
+}
+
+/// Check that no table is renamed to the previous name of the table of another object that
+/// is kept, as the tables would have to be renamed in a specific order
+fn check_table_names(objects: &[(Option<&Object>, Option<&Object>)]) -> Result<(), Error> {
+    for (from, to) in objects {
+        let (from, to) = match (from, to) {
+            (Some(from), Some(to)) if from.get_table_name() != to.get_table_name() => (from, to),
+            _ => continue,
+        };
+        let previous = objects.iter().find(|(other_from, other_to)| {
+            other_to.is_some() && other_from.is_some_and(|o| o.get_name() != from.get_name() && o.get_table_name() == to.get_table_name())
+        });
+        if let Some((Some(previous), _)) = previous {
+            return Err(Error::UnsupportedMigration {
+                object: to.get_name().clone(),
+                reason: format!("the table '{}' is still used by '{}'", to.get_table_name(), previous.get_name()),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Generate the statements migrating the table of an object from a version to another.
+/// The attributes are given as pairs (previous version, next version)
+fn sqlite_migrate_table(
+    (from, from_db): (Option<&Object>, &ObjectDB),
+    (to, to_db): (Option<&Object>, &ObjectDB),
+    attributes: &[(Option<&Attribute>, Option<&Attribute>)],
+) -> Result<String, Error> {
+    let (from, to) = match (from, to) {
+        (None, Some(to)) => return Ok(format!("\n{}{}\n", sqlite_table(to, to_db), sqlite_indexes(to))),
+        (Some(from), None) => return Ok(format!("\nDROP TABLE {};\n", from.get_table_name())),
+        (Some(from), Some(to)) => (from, to),
+        (None, None) => return Ok(String::new()),
+    };
+    let unsupported = |reason: String| Error::UnsupportedMigration {
+        object: to.get_name().clone(),
+        reason,
+    };
+    //the identifiers can not be converted, and the references to them would be broken
+    if from.get_id_type() != to.get_id_type() {
+        return Err(unsupported(String::from("the type of the identifier changes")));
+    }
+    for pair in attributes {
+        match pair {
+            (None, Some(to_at)) if !to_at.is_optional() && to_at.get_default().is_none() => {
+                return Err(unsupported(format!(
+                    "the mandatory attribute '{}' is added without default value",
+                    to_at.get_name()
+                )));
+            }
+            //the rows without value could not be copied
+            (Some(from_at), Some(to_at)) if from_at.is_optional() && !to_at.is_optional() && to_at.get_default().is_none() => {
+                return Err(unsupported(format!(
+                    "the attribute '{}' becomes mandatory without default value",
+                    to_at.get_name()
+                )));
+            }
+            _ => {}
+        }
+    }
+    match can_alter_table(from, from_db, to, to_db, attributes) {
+        true => Ok(sqlite_alter_table(from, to, to_db, attributes)),
+        false => Ok(sqlite_rebuild_table(from, to, to_db, attributes)),
+    }
+}
+
+/// Check if the table can be altered instead of being rebuilt: the columns are only renamed
+/// or added at the end, and the added columns can be filled for the existing rows
+fn can_alter_table(
+    from: &Object,
+    from_db: &ObjectDB,
+    to: &Object,
+    to_db: &ObjectDB,
+    attributes: &[(Option<&Attribute>, Option<&Attribute>)],
+) -> bool {
+    let mut previous_index = None;
+    let mut added = false;
+    for pair in attributes {
+        match pair {
+            (Some(from_at), Some(to_at)) => {
+                let index = from.get_attributes().position(|at| at.get_name() == from_at.get_name());
+                let same_column = sqlite_column(&from_at.with_name(to_at.get_name()), from_db) == sqlite_column(to_at, to_db)
+                    && from_at.get_reference() == to_at.get_reference()
+                    && from_at.is_unique() == to_at.is_unique();
+                //the columns keep their order, the added ones being placed at the end
+                if !same_column || added || index < previous_index {
+                    return false;
+                }
+                previous_index = index;
+            }
+            (None, Some(to_at)) => {
+                //the foreign keys and the unique constraints are table constraints
+                let fillable = to_at.is_optional() || to_at.get_default().is_some();
+                if !fillable || to_at.get_reference().is_some() || to_at.is_unique() {
+                    return false;
+                }
+                added = true;
+            }
+            (Some(_from_at), None) => return false,
+            (None, None) => {}
+        }
+    }
+    //the unique groups are table constraints as well
+    let renamed = |name: &String| -> String {
+        match attributes.iter().find(|(from_at, _)| from_at.is_some_and(|at| at.get_name() == name)) {
+            Some((_, Some(to_at))) => to_at.get_name().clone(),
+            _ => name.clone(),
+        }
+    };
+    let from_groups: Vec<Vec<String>> = from.get_unique_groups().map(|group| group.iter().map(renamed).collect()).collect();
+    let to_groups: Vec<&Vec<String>> = to.get_unique_groups().collect();
+    from_groups.iter().eq(to_groups)
+}
+
+/// Generate the statements altering the table of an object, which must be possible
+fn sqlite_alter_table(
+    from: &Object,
+    to: &Object,
+    to_db: &ObjectDB,
+    attributes: &[(Option<&Attribute>, Option<&Attribute>)],
+) -> String {
+    let table_name = to.get_table_name();
+    let mut statements = String::new();
+    if from.get_table_name() != table_name {
+        statements += &format!("\nALTER TABLE {} RENAME TO {table_name};", from.get_table_name());
+    }
+    for pair in attributes {
+        match pair {
+            (Some(from_at), Some(to_at)) if from_at.get_name() != to_at.get_name() => {
+                statements += &format!(
+                    "\nALTER TABLE {table_name} RENAME COLUMN {} TO {};",
+                    from_at.get_name(),
+                    to_at.get_name()
+                );
+            }
+            (None, Some(to_at)) => {
+                statements += &format!("\nALTER TABLE {table_name} ADD COLUMN {};", sqlite_column(to_at, to_db));
+            }
+            _ => {}
+        }
+    }
+    //the indexes are named after the table and the column, they are recreated when one of them is renamed
+    let from_indexes = sqlite_index_list(from);
+    let to_indexes = sqlite_index_list(to);
+    for (index_name, statement) in &from_indexes {
+        if !to_indexes.iter().any(|(_name, other)| other == statement) {
+            statements += &format!("\nDROP INDEX {index_name};");
+        }
+    }
+    for (_index_name, statement) in &to_indexes {
+        if !from_indexes.iter().any(|(_name, other)| other == statement) {
+            statements += &format!("\n{statement}");
+        }
+    }
+    match statements.is_empty() {
+        true => statements,
+        false => format!("{statements}\n"),
+    }
+}
+
+/// Generate the statements rebuilding the table of an object: the new table is created,
+/// filled with the content of the previous one, which is dropped, then renamed
+fn sqlite_rebuild_table(
+    from: &Object,
+    to: &Object,
+    to_db: &ObjectDB,
+    attributes: &[(Option<&Attribute>, Option<&Attribute>)],
+) -> String {
+    let table_name = to.get_table_name();
+    let new_table_name = format!("{table_name}_new");
+    let mut columns = String::new();
+    let mut values = String::new();
+    for pair in attributes {
+        let (from_at, to_at) = match pair {
+            (from_at, Some(to_at)) => (from_at, to_at),
+            (_, None) => continue,
+        };
+        let default = to_at.get_default().map(|value| sqlite_default(to_at, value));
+        let value = match (from_at, default) {
+            //the existing rows without value take the default one
+            (Some(from_at), Some(default)) if from_at.is_optional() && !to_at.is_optional() => {
+                format!("COALESCE({}, {default})", from_at.get_name())
+            }
+            (Some(from_at), _) => from_at.get_name().clone(),
+            (None, Some(default)) => default,
+            (None, None) => String::from("NULL"),
+        };
+        columns += &format!(", {}", to_at.get_name());
+        values += &format!(", {value}");
+    }
+    format!(
+        "\n{create}INSERT INTO {new_table_name} (id{columns}, version)\n    SELECT id{values}, version FROM {from_table_name};\nDROP TABLE {from_table_name};\nALTER TABLE {new_table_name} RENAME TO {table_name};{indexes}\n",
+        create = sqlite_create_table(to, to_db, &new_table_name),
+        from_table_name = from.get_table_name(),
+        indexes = sqlite_indexes(to),
+    )
+}
+
+/// Retrieve the SQL literal of the default value of an attribute, with the
+/// representation used on database
+fn sqlite_default(attribute: &Attribute, value: &str) -> String {
+    match attribute.get_type().get_base_type() {
+        BaseAttributeType::Integer | BaseAttributeType::Float => value.to_string(),
+        BaseAttributeType::Boolean => String::from(if value == "true" { "1" } else { "0" }),
+        //decimals are stored without their leading and trailing zeros
+        BaseAttributeType::Decimal { .. } => {
+            let (sign, digits) = match value.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", value),
+            };
+            let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+            let integer = match integer.trim_start_matches('0') {
+                "" => "0",
+                other => other,
+            };
+            match fraction.trim_end_matches('0') {
+                "" if integer == "0" => String::from("'0'"),
+                "" => format!("'{sign}{integer}'"),
+                fraction => format!("'{sign}{integer}.{fraction}'"),
+            }
+        }
+        BaseAttributeType::Uuid => format!("'{}'", value.to_lowercase()),
+        _ => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+/// Retrieve the CHECK clauses enforcing the constraints of an attribute.
+/// The pattern is not checked, as SQLite does not provide regular expressions
+fn sqlite_constraints(attribute: &Attribute) -> String {
+    let column_name = attribute.get_name();
+    let constraints = attribute.get_constraints();
+    let base_type = attribute.get_type().get_base_type();
+    let literal = |v: &String| match base_type {
+        BaseAttributeType::Integer | BaseAttributeType::Float => v.clone(),
+        _ => format!("'{}'", v.replace('\'', "''")),
+    };
+    let mut checks = String::new();
+    //decimals are stored as text and SQLite can only compare them approximately,
+    //their bounds are checked exactly by the generated code only
+    let exact_bounds = !matches!(base_type, BaseAttributeType::Decimal { .. });
+    if let Some(min) = constraints.get_min().filter(|_| exact_bounds) {
+        checks += &format!(" CHECK ({column_name} >= {})", literal(min));
+    }
+    if let Some(max) = constraints.get_max().filter(|_| exact_bounds) {
+        checks += &format!(" CHECK ({column_name} <= {})", literal(max));
+    }
+    if let Some(min_length) = constraints.get_min_length() {
+        checks += &format!(" CHECK (length({column_name}) >= {min_length})");
+    }
+    if let Some(max_length) = constraints.get_max_length() {
+        checks += &format!(" CHECK (length({column_name}) <= {max_length})");
+    }
+    if let Some(values) = constraints.get_one_of() {
+        let values: Vec<String> = values.iter().map(literal).collect();
+        checks += &format!(" CHECK ({column_name} IN ({}))", values.join(", "));
+    }
+    checks
+}
+
+fn sqlite_table(obj: &Object, db: &ObjectDB) -> String {
+    sqlite_create_table(obj, db, obj.get_table_name())
+}
+
+/// Create the table storing the object, with the given name
+fn sqlite_create_table(obj: &Object, db: &ObjectDB, table_name: &str) -> String {
     let mut columns = String::new();
     let mut foreign_keys = String::new();
     let mut uniques = String::new();
     for attribute in obj.get_attributes() {
         let column_name = attribute.get_name();
-        let nullable = match attribute.get_type() {
-            crate::AttributeType::Mandatory(_) => String::from(" NOT NULL"),
-            crate::AttributeType::Optional(_) => String::new(),
-        };
-        let sql_type = match attribute.get_type().get_base_type() {
-            crate::BaseAttributeType::Integer => String::from("BIGINT"),
-            crate::BaseAttributeType::String => String::from("VARCHAR"),
-            crate::BaseAttributeType::Reference(refered) => {
-                foreign_keys = format!(
-                    "{previous},\n    FOREIGN KEY({column_name}) REFERENCES {refered}(id)",
-                    previous = foreign_keys
-                );
-                String::from("INTEGER")
-            }
-        };
-        if attribute.is_indexable() {
+        if let Some(refered) = attribute.get_reference() {
+            //the foreign key targets the table of the referenced object, not its name
+            let refered_table = db.get_object(refered).map_or(refered, |o| o.get_table_name());
+            foreign_keys = format!("{foreign_keys},\n    FOREIGN KEY({column_name}) REFERENCES {refered_table}(id)");
+        }
+        if attribute.is_unique() {
             uniques = format!("{uniques},\n    UNIQUE({column_name})");
         }
-        columns = format!("{columns},\n    {column_name} {sql_type}{nullable}")
+        columns = format!("{columns},\n    {}", sqlite_column(attribute, db));
+    }
+    for group in obj.get_unique_groups() {
+        uniques = format!("{uniques},\n    UNIQUE({})", group.join(", "));
     }
     format!(
-        "CREATE TABLE {table_name} (\n    id INTEGER PRIMARY KEY NOT NULL{columns},\n    version BIGINT NOT NULL{foreign_keys}{uniques}\n);\n",
-        
+        "CREATE TABLE {table_name} (\n    id {id_type} PRIMARY KEY NOT NULL{columns},\n    version BIGINT NOT NULL{foreign_keys}{uniques}\n);\n",
+        id_type = sqlite_id_type(obj.get_id_type())
     )
 }
 
+/// Retrieve the definition of the column storing an attribute, without the
+/// table constraints (foreign key and unique) it may be part of
+fn sqlite_column(attribute: &Attribute, db: &ObjectDB) -> String {
+    let column_name = attribute.get_name();
+    let nullable = match attribute.get_type() {
+        crate::AttributeType::Mandatory(_) => String::from(" NOT NULL"),
+        crate::AttributeType::Optional(_) => String::new(),
+    };
+    let mut check = String::new();
+    let sql_type = match attribute.get_type().get_base_type() {
+        crate::BaseAttributeType::Integer => String::from("BIGINT"),
+        crate::BaseAttributeType::Float => String::from("REAL"),
+        //decimals are stored as text: the NUMERIC affinity would convert them to floating points
+        crate::BaseAttributeType::Decimal { .. } => String::from("TEXT"),
+        crate::BaseAttributeType::Boolean => {
+            check = format!(" CHECK ({column_name} IN (0, 1))");
+            String::from("BOOLEAN")
+        }
+        crate::BaseAttributeType::String => String::from("VARCHAR"),
+        crate::BaseAttributeType::Date => String::from("DATE"),
+        crate::BaseAttributeType::Time => String::from("TIME"),
+        crate::BaseAttributeType::Timestamp => String::from("TIMESTAMP"),
+        //durations are stored as a number of microseconds
+        crate::BaseAttributeType::Duration => String::from("BIGINT"),
+        crate::BaseAttributeType::Enum(e) => {
+            if let Some(enumeration) = db.get_enum(e) {
+                let mut values = String::new();
+                for variant in enumeration.get_variants() {
+                    if !values.is_empty() {
+                        values += ", ";
+                    }
+                    values = format!("{values}'{}'", variant.get_name().replace('\'', "''"));
+                }
+                check = format!(" CHECK ({column_name} IN ({values}))");
+            }
+            String::from("VARCHAR")
+        }
+        //UUIDs are stored with their hyphenated representation
+        crate::BaseAttributeType::Uuid => {
+            check = format!(" CHECK (length({column_name}) = 36)");
+            String::from("CHAR(36)")
+        }
+        crate::BaseAttributeType::Json | crate::BaseAttributeType::TypedJson(_) => {
+            //older versions of SQLite consider NULL as an invalid document
+            check = if attribute.is_optional() {
+                format!(" CHECK ({column_name} IS NULL OR json_valid({column_name}))")
+            } else {
+                format!(" CHECK (json_valid({column_name}))")
+            };
+            String::from("TEXT")
+        }
+        crate::BaseAttributeType::Bytes => {
+            if let Some(max_size) = attribute.get_max_size() {
+                check = format!(" CHECK (length({column_name}) <= {max_size})");
+            }
+            String::from("BLOB")
+        }
+        crate::BaseAttributeType::Reference(_) => sqlite_id_type(attribute.get_reference_id_type()),
+    };
+    let default = match attribute.get_default() {
+        Some(value) => format!(" DEFAULT {}", sqlite_default(attribute, value)),
+        None => String::new(),
+    };
+    let collation = if attribute.get_constraints().is_case_insensitive() {
+        " COLLATE NOCASE"
+    } else {
+        ""
+    };
+    let constraints = sqlite_constraints(attribute);
+    format!("{column_name} {sql_type}{collation}{nullable}{default}{check}{constraints}")
+}
+
+//Retrieve the sql type used to store an identifier.
+fn sqlite_id_type(id_type: IdentifierType) -> String {
+    match id_type {
+        //an INTEGER PRIMARY KEY is an alias of the rowid, automatically assigned by SQLite
+        IdentifierType::Integer => String::from("INTEGER"),
+        IdentifierType::Uuid => String::from("CHAR(36)"),
+    }
+}
+
+/// Create the non unique indexes of the table: the ones explicitly requested and
+/// the ones on the references. The unique constraints are already backed by an
+/// index created by SQLite, which can also be used for the lookups on the first
+/// column of a unique group. The unique groups with optional attributes are
+/// completed by partial unique indexes, as SQLite considers the NULL values as distinct
 fn sqlite_indexes(obj: &Object) -> String {
-    let table_name = obj.get_table_name();
     let mut indexes = String::new();
+    for (_index_name, statement) in sqlite_index_list(obj) {
+        indexes = format!("{indexes}\n{statement}");
+    }
+    indexes
+}
+
+/// Retrieve the names and the creation statements of the indexes of the table, other than
+/// the ones backing the unique constraints
+fn sqlite_index_list(obj: &Object) -> Vec<(String, String)> {
+    let table_name = obj.get_table_name();
+    let mut indexes = Vec::new();
     for attribute in obj.get_attributes() {
-        if attribute.is_indexable() {
-            let column_name = attribute.get_name();
-            indexes = format!("{indexes}\nCREATE INDEX idx_{table_name}_{column_name} ON {table_name}({column_name});");
+        let column_name = attribute.get_name();
+        let covered = attribute.is_unique()
+            || obj
+                .get_unique_groups()
+                .any(|group| group.first() == Some(column_name));
+        if attribute.is_indexed() || (attribute.get_reference().is_some() && !covered) {
+            let index_name = format!("idx_{table_name}_{column_name}");
+            let statement = format!("CREATE INDEX {index_name} ON {table_name}({column_name});");
+            indexes.push((index_name, statement));
+        }
+    }
+    for group in obj.get_unique_groups() {
+        let optional: Vec<&String> = group
+            .iter()
+            .filter(|name| obj.get_attributes().any(|at| at.get_name() == *name && at.is_optional()))
+            .collect();
+        //an index for each combination of optional attributes without value, the other
+        // attributes being unique among the rows having no value for them
+        for combination in 1..(1_usize << optional.len()) {
+            let nulls: Vec<&String> =
+                optional.iter().enumerate().filter(|(i, _name)| combination & (1 << i) != 0).map(|(_i, name)| *name).collect();
+            let columns: Vec<&String> = group.iter().filter(|name| !nulls.contains(name)).collect();
+            let index_name = format!(
+                "uq_{table_name}_{}_null_{}",
+                group.join("_"),
+                nulls.iter().map(|name| name.as_str()).collect::<Vec<&str>>().join("_")
+            );
+            let columns = match columns.is_empty() {
+                //a single row may have no value for all the attributes
+                true => String::from("(1)"),
+                false => columns.iter().map(|name| name.as_str()).collect::<Vec<&str>>().join(", "),
+            };
+            let condition = nulls.iter().map(|name| format!("{name} IS NULL")).collect::<Vec<String>>().join(" AND ");
+            let statement = format!("CREATE UNIQUE INDEX {index_name} ON {table_name}({columns}) WHERE {condition};");
+            indexes.push((index_name, statement));
         }
     }
     indexes
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, AttributeType, BaseAttributeType, Error, IdentifierType, Object, ObjectDB};
+
+    use super::{generate_sqlite_install, generate_sqlite_migration, sqlite_indexes, sqlite_table};
+
+    fn empty_db() -> ObjectDB {
+        serde_json::from_slice(b"{\"rust_destination\": \"\", \"objects\": []}").unwrap()
+    }
+
+    #[test]
+    fn boolean_column() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"users\", \"name\": \"User\", \"attr\": [
+                {\"name\": \"active\", \"is\": {\"Mandatory\": \"Boolean\"}},
+                {\"name\": \"verified\", \"is\": {\"Optional\": \"Boolean\"}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE users (
+    id INTEGER PRIMARY KEY NOT NULL,
+    active BOOLEAN NOT NULL CHECK (active IN (0, 1)),
+    verified BOOLEAN CHECK (verified IN (0, 1)),
+    version BIGINT NOT NULL
+);
+"
+            ),
+            sqlite_table(&object, &empty_db())
+        );
+    }
+
+    #[test]
+    fn enum_column() {
+        let db: ObjectDB = serde_json::from_slice(
+            b"{\"rust_destination\": \"\", \"objects\": [], \"enums\": [
+                {\"name\": \"Status\", \"variants\": [{\"name\": \"Open\"}, {\"name\": \"Closed\"}]}
+            ]}",
+        )
+        .unwrap();
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"tickets\", \"name\": \"Ticket\", \"attr\": [
+                {\"name\": \"status\", \"is\": {\"Mandatory\": {\"Enum\": \"Status\"}}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE tickets (
+    id INTEGER PRIMARY KEY NOT NULL,
+    status VARCHAR NOT NULL CHECK (status IN ('Open', 'Closed')),
+    version BIGINT NOT NULL
+);
+"
+            ),
+            sqlite_table(&object, &db)
+        );
+    }
+
+    #[test]
+    fn uuid_identifier() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"devices\", \"name\": \"Device\", \"id\": \"Uuid\", \"attr\": [
+                {\"name\": \"serial\", \"is\": {\"Optional\": \"Uuid\"}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE devices (
+    id CHAR(36) PRIMARY KEY NOT NULL,
+    serial CHAR(36) CHECK (length(serial) = 36),
+    version BIGINT NOT NULL
+);
+"
+            ),
+            sqlite_table(&object, &empty_db())
+        );
+    }
+
+    #[test]
+    fn json_column() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"users\", \"name\": \"User\", \"attr\": [
+                {\"name\": \"settings\", \"is\": {\"Mandatory\": \"Json\"}},
+                {\"name\": \"profile\", \"is\": {\"Optional\": {\"TypedJson\": \"crate::Profile\"}}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE users (
+    id INTEGER PRIMARY KEY NOT NULL,
+    settings TEXT NOT NULL CHECK (json_valid(settings)),
+    profile TEXT CHECK (profile IS NULL OR json_valid(profile)),
+    version BIGINT NOT NULL
+);
+"
+            ),
+            sqlite_table(&object, &empty_db())
+        );
+    }
+
+    #[test]
+    fn default_values() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"accounts\", \"name\": \"Account\", \"attr\": [
+                {\"name\": \"owner\", \"is\": {\"Mandatory\": \"String\"}, \"default\": \"nobody's\"},
+                {\"name\": \"active\", \"is\": {\"Mandatory\": \"Boolean\"}, \"default\": \"true\"},
+                {\"name\": \"balance\", \"is\": {\"Mandatory\": {\"Decimal\": {\"precision\": 10, \"scale\": 2}}}, \"default\": \"-00.50\"}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE accounts (
+    id INTEGER PRIMARY KEY NOT NULL,
+    owner VARCHAR NOT NULL DEFAULT 'nobody''s',
+    active BOOLEAN NOT NULL DEFAULT 1 CHECK (active IN (0, 1)),
+    balance TEXT NOT NULL DEFAULT '-0.5',
+    version BIGINT NOT NULL
+);
+"
+            ),
+            sqlite_table(&object, &empty_db())
+        );
+    }
+
+    #[test]
+    fn constrained_columns() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"users\", \"name\": \"User\", \"attr\": [
+                {\"name\": \"login\", \"is\": {\"Mandatory\": \"String\"}, \"min_length\": 3, \"pattern\": \"[a-z]+\", \"case_insensitive\": true},
+                {\"name\": \"role\", \"is\": {\"Optional\": \"String\"}, \"one_of\": [\"admin\", \"o'neil\"]},
+                {\"name\": \"age\", \"is\": {\"Mandatory\": \"Integer\"}, \"min\": \"0\", \"max\": \"150\"},
+                {\"name\": \"rate\", \"is\": {\"Mandatory\": {\"Decimal\": {\"precision\": 4, \"scale\": 2}}}, \"min\": \"0.1\"}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE users (
+    id INTEGER PRIMARY KEY NOT NULL,
+    login VARCHAR COLLATE NOCASE NOT NULL CHECK (length(login) >= 3),
+    role VARCHAR CHECK (role IN ('admin', 'o''neil')),
+    age BIGINT NOT NULL CHECK (age >= 0) CHECK (age <= 150),
+    rate TEXT NOT NULL,
+    version BIGINT NOT NULL
+);
+"
+            ),
+            sqlite_table(&object, &empty_db())
+        );
+    }
+
+    #[test]
+    fn composite_unique() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"products\", \"name\": \"Product\", \"unique\": [[\"tenant\", \"code\"]], \"attr\": [
+                {\"name\": \"tenant\", \"is\": {\"Mandatory\": \"Integer\"}},
+                {\"name\": \"code\", \"is\": {\"Mandatory\": \"String\"}}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "CREATE TABLE products (
+    id INTEGER PRIMARY KEY NOT NULL,
+    tenant BIGINT NOT NULL,
+    code VARCHAR NOT NULL,
+    version BIGINT NOT NULL,
+    UNIQUE(tenant, code)
+);
+"
+            ),
+            sqlite_table(&object, &empty_db())
+        );
+        assert_eq!(String::new(), sqlite_indexes(&object));
+    }
+
+    #[test]
+    fn optional_composite_unique() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"nodes\", \"name\": \"Node\", \"unique\": [[\"parent\", \"name\"], [\"parent\", \"label\"]], \"attr\": [
+                {\"name\": \"parent\", \"is\": {\"Optional\": \"Integer\"}},
+                {\"name\": \"name\", \"is\": {\"Mandatory\": \"String\"}},
+                {\"name\": \"label\", \"is\": {\"Optional\": \"String\"}}
+            ]}",
+        )
+        .unwrap();
+        //the NULL values being distinct for SQLite, the rows without value are covered by partial indexes
+        assert_eq!(
+            String::from(
+                "
+CREATE UNIQUE INDEX uq_nodes_parent_name_null_parent ON nodes(name) WHERE parent IS NULL;
+CREATE UNIQUE INDEX uq_nodes_parent_label_null_parent ON nodes(label) WHERE parent IS NULL;
+CREATE UNIQUE INDEX uq_nodes_parent_label_null_label ON nodes(parent) WHERE label IS NULL;
+CREATE UNIQUE INDEX uq_nodes_parent_label_null_parent_label ON nodes((1)) WHERE parent IS NULL AND label IS NULL;"
+            ),
+            sqlite_indexes(&object)
+        );
+    }
+
+    #[test]
+    fn indexes() {
+        let object: Object = serde_json::from_slice(
+            b"{\"tbnm\": \"tickets\", \"name\": \"Ticket\", \"attr\": [
+                {\"name\": \"code\", \"is\": {\"Mandatory\": \"String\"}, \"indexable\": true},
+                {\"name\": \"status\", \"is\": {\"Mandatory\": \"String\"}, \"indexed\": true},
+                {\"name\": \"owner\", \"is\": {\"Optional\": {\"Reference\": \"User\"}}},
+                {\"name\": \"queue\", \"is\": {\"Mandatory\": {\"Reference\": \"Queue\"}}, \"unique\": true, \"indexed\": true}
+            ]}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from(
+                "\nCREATE INDEX idx_tickets_status ON tickets(status);\nCREATE INDEX idx_tickets_owner ON tickets(owner);"
+            ),
+            sqlite_indexes(&object)
+        );
+        assert!(sqlite_table(&object, &empty_db()).contains("UNIQUE(code),\n    UNIQUE(queue)"));
+    }
+
+    #[test]
+    fn migration() {
+        let string = |name: &str| Attribute::new(name, AttributeType::Mandatory(BaseAttributeType::String));
+        let old = ObjectDB::builder()
+            .object("Author")
+            .table("authors")
+            .attr(string("name"))
+            .object("Book")
+            .table("books")
+            .attr(string("title"))
+            .attr(Attribute::new("pages", AttributeType::Optional(BaseAttributeType::Integer)))
+            .build()
+            .unwrap();
+        let new = ObjectDB::builder()
+            .object("Author")
+            .table("writers")
+            .renamed_from("authors")
+            .attr(string("full_name").renamed_from("name").indexed())
+            .attr(Attribute::new("born", AttributeType::Optional(BaseAttributeType::Integer)))
+            .object("Book")
+            .table("books")
+            .attr(string("title"))
+            .attr(Attribute::new("pages", AttributeType::Mandatory(BaseAttributeType::Integer)).default_value("0"))
+            .build()
+            .unwrap();
+
+        let (up, down) = generate_sqlite_migration(&old, &new).unwrap();
+        //the authors are altered, the books rebuilt as the pages become mandatory
+        assert_eq!(
+            String::from(
+                "-- Migration to the new version of the model, changes:
+--   The table of 'Author' is renamed from 'authors' to 'writers' (database: compatible, code: compatible)
+--   The attribute 'Author.name' is renamed 'full_name' (database: compatible, code: breaking)
+--   The attribute 'Author.full_name' becomes indexed (database: compatible, code: compatible)
+--   The attribute 'Author.born' is added (database: compatible, code: breaking)
+--   The attribute 'Book.pages' becomes mandatory (database: breaking, code: breaking)
+
+ALTER TABLE authors RENAME TO writers;
+ALTER TABLE writers RENAME COLUMN name TO full_name;
+ALTER TABLE writers ADD COLUMN born BIGINT;
+CREATE INDEX idx_writers_full_name ON writers(full_name);
+
+CREATE TABLE books_new (
+    id INTEGER PRIMARY KEY NOT NULL,
+    title VARCHAR NOT NULL,
+    pages BIGINT NOT NULL DEFAULT 0,
+    version BIGINT NOT NULL
+);
+INSERT INTO books_new (id, title, pages, version)
+    SELECT id, title, COALESCE(pages, 0), version FROM books;
+DROP TABLE books;
+ALTER TABLE books_new RENAME TO books;
+"
+            ),
+            up
+        );
+        //removing a column requires to rebuild the table
+        assert!(down.contains(
+            "INSERT INTO authors_new (id, name, version)
+    SELECT id, full_name, version FROM writers;
+DROP TABLE writers;
+ALTER TABLE authors_new RENAME TO authors;"
+        ));
+        assert!(down.contains("    SELECT id, title, pages, version FROM books;"));
+
+        //no change
+        let (up, _down) = generate_sqlite_migration(&new, &new).unwrap();
+        assert_eq!("-- Migration to the new version of the model, changes:\n--   none\n", up);
+
+        //the existing rows can not be migrated
+        let uuid = ObjectDB::builder()
+            .object("Author")
+            .table("writers")
+            .id_type(IdentifierType::Uuid)
+            .attr(string("full_name"))
+            .attr(Attribute::new("born", AttributeType::Optional(BaseAttributeType::Integer)))
+            .object("Book")
+            .table("books")
+            .attr(string("title"))
+            .attr(Attribute::new("pages", AttributeType::Mandatory(BaseAttributeType::Integer)).default_value("0"))
+            .build()
+            .unwrap();
+        assert!(matches!(
+            generate_sqlite_migration(&new, &uuid),
+            Err(Error::UnsupportedMigration { object, .. }) if object == "Author"
+        ));
+        let isbn = ObjectDB::builder()
+            .object("Author")
+            .table("authors")
+            .attr(string("name"))
+            .object("Book")
+            .table("books")
+            .attr(string("title"))
+            .attr(Attribute::new("pages", AttributeType::Optional(BaseAttributeType::Integer)))
+            .attr(string("isbn"))
+            .build()
+            .unwrap();
+        assert!(matches!(
+            generate_sqlite_migration(&old, &isbn),
+            Err(Error::UnsupportedMigration { object, .. }) if object == "Book"
+        ));
+        //the column is added back when reverting its removal
+        let error = generate_sqlite_migration(&isbn, &old).unwrap_err();
+        assert!(error.to_string().contains("when reverting the migration"));
+        //the pages without value can not become mandatory, nor be reverted to it
+        let pages = |is: AttributeType| {
+            ObjectDB::builder()
+                .object("Book")
+                .table("books")
+                .attr(Attribute::new("pages", is))
+                .build()
+                .unwrap()
+        };
+        let optional = pages(AttributeType::Optional(BaseAttributeType::Integer));
+        let mandatory = pages(AttributeType::Mandatory(BaseAttributeType::Integer));
+        let error = generate_sqlite_migration(&optional, &mandatory).unwrap_err();
+        assert!(error.to_string().contains("the attribute 'pages' becomes mandatory without default value"));
+        let error = generate_sqlite_migration(&mandatory, &optional).unwrap_err();
+        assert!(error.to_string().contains("when reverting the migration"));
+    }
+
+    #[test]
+    fn table_taken_over() {
+        let name = || Attribute::new("name", AttributeType::Mandatory(BaseAttributeType::String));
+        let old = ObjectDB::builder()
+            .object("Author")
+            .table("authors")
+            .attr(name())
+            .object("Shelf")
+            .table("shelves")
+            .build()
+            .unwrap();
+        let new = ObjectDB::builder()
+            .object("Contributor")
+            .table("authors")
+            .attr(name())
+            .object("Writer")
+            .table("writers")
+            .renamed_from("Author")
+            .attr(name())
+            .object("Shelf")
+            .table("shelves")
+            .build()
+            .unwrap();
+        //the table is renamed before being created again, and dropped before being renamed back
+        let (up, down) = generate_sqlite_migration(&old, &new).unwrap();
+        let rename = up.find("ALTER TABLE authors RENAME TO writers;").unwrap();
+        assert!(rename < up.find("CREATE TABLE authors (").unwrap());
+        let drop = down.find("DROP TABLE authors;").unwrap();
+        let rename = down.find("ALTER TABLE writers RENAME TO authors;").unwrap();
+        assert!(drop < rename);
+
+        //the table of a kept object can not be renamed to the table of another kept object
+        let swapped = ObjectDB::builder()
+            .object("Author")
+            .table("shelves")
+            .attr(name())
+            .object("Shelf")
+            .table("racks")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            generate_sqlite_migration(&old, &swapped),
+            Err(Error::UnsupportedMigration { object, .. }) if object == "Author"
+        ));
+    }
+
+    #[test]
+    fn foreign_key_table() {
+        let db = ObjectDB::builder()
+            .object("Author")
+            .table("authors")
+            .attr(Attribute::new("name", AttributeType::Mandatory(BaseAttributeType::String)))
+            .object("Book")
+            .table("books")
+            .attr(Attribute::new(
+                "author",
+                AttributeType::Mandatory(BaseAttributeType::Reference(String::from("Author"))),
+            ))
+            .build()
+            .unwrap();
+        let install = generate_sqlite_install(&db);
+        assert!(install.contains("    FOREIGN KEY(author) REFERENCES authors(id)\n"));
+    }
+}